@@ -0,0 +1,45 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    data::local_storage,
+    leptos_ext::{rc_signal::RcSignal, ReadSignalExt},
+};
+
+const STORAGE_KEY: &str = "locallyvalid-bookmarks";
+
+/// Pinned entries, starting from the ones last saved.
+///
+/// Changes are saved so they survive reloads.
+pub fn bookmarks() -> RcSignal<BTreeSet<u128>> {
+    let bookmarks = RcSignal::new(load().unwrap_or_default());
+    bookmarks.for_each_after_first(save);
+    bookmarks
+}
+
+/// Saved as a list of decimal strings, like the ids in the data.
+fn load() -> Option<BTreeSet<u128>> {
+    let raw = match local_storage()?.get_item(STORAGE_KEY) {
+        Ok(raw) => raw?,
+        Err(e) => {
+            log::warn!("Failed to read saved bookmarks: {e:?}");
+            return None;
+        }
+    };
+    match serde_json::from_str::<Vec<String>>(&raw) {
+        Ok(ids) => Some(ids.iter().filter_map(|id| id.parse().ok()).collect()),
+        Err(e) => {
+            log::warn!("Ignoring invalid saved bookmarks: {e}");
+            None
+        }
+    }
+}
+fn save(ids: &BTreeSet<u128>) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let ids: Vec<String> = ids.iter().map(u128::to_string).collect();
+    let raw = serde_json::to_string(&ids).unwrap();
+    if let Err(e) = storage.set_item(STORAGE_KEY, &raw) {
+        log::warn!("Failed to save bookmarks: {e:?}");
+    }
+}
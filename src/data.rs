@@ -0,0 +1,1251 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    error::Error,
+    fmt,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Data {
+    entries: BTreeMap<u128, Entry>,
+    children: BTreeMap<u128, BTreeSet<u128>>,
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub text: String,
+    #[serde(with = "decimal::vec")]
+    pub parents: Vec<u128>,
+    /// Missing for entries saved before timestamps were tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+    /// The last change to the text or parents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// A measured quantity, to put others in perspective.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Datapoint {
+    pub name: String,
+    pub size: f64,
+    pub standard_uncertainty: Option<f64>,
+    pub comment: Option<String>,
+    /// Urls of the sources.
+    pub refs: Vec<String>,
+}
+
+/// The on-disk format, ids are encoded as decimal strings.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+struct Entries(#[serde(with = "decimal::map")] BTreeMap<u128, Entry>);
+
+impl Data {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&Entries(self.entries.clone())).unwrap()
+    }
+    /// Fails on invalid JSON, ids that point at missing entries, or cycles.
+    pub fn from_json(raw: &str) -> Result<Self, DataError> {
+        let Entries(entries) = serde_json::from_str(raw)?;
+        let data = Self::from_raw(entries);
+        data.validate().map_err(DataError::Invalid)?;
+        data.topological_order()?;
+        Ok(data)
+    }
+    /// Saves to `localStorage`, logging any failure.
+    pub fn save_local(&self) {
+        let Some(storage) = local_storage() else {
+            return;
+        };
+        if let Err(e) = storage.set_item(STORAGE_KEY, &self.to_json()) {
+            log::warn!("Failed to save data: {e:?}");
+        }
+    }
+    /// Restores what was last saved to `localStorage`, if anything.
+    pub fn load_local() -> Option<Self> {
+        let raw = match local_storage()?.get_item(STORAGE_KEY) {
+            Ok(raw) => raw?,
+            Err(e) => {
+                log::warn!("Failed to read saved data: {e:?}");
+                return None;
+            }
+        };
+        match Self::from_json(&raw) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                log::warn!("Ignoring invalid saved data: {e}");
+                None
+            }
+        }
+    }
+
+    pub fn from_raw(entries: BTreeMap<u128, Entry>) -> Self {
+        let mut children: BTreeMap<u128, BTreeSet<u128>> = BTreeMap::new();
+        for (id, entry) in &entries {
+            for p in &entry.parents {
+                children.entry(*p).or_default().insert(*id);
+            }
+        }
+
+        Self { entries, children }
+    }
+
+    /// Checks that every parent and child id points at an existing entry.
+    pub fn validate(&self) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues = vec![];
+        for (&entry, e) in &self.entries {
+            for &parent in &e.parents {
+                if !self.entries.contains_key(&parent) {
+                    issues.push(ValidationIssue::MissingParent { entry, parent });
+                }
+            }
+        }
+        for (&entry, children) in &self.children {
+            if !self.entries.contains_key(&entry) {
+                continue; // Already reported as a missing parent above.
+            }
+            for &child in children {
+                if !self.entries.contains_key(&child) {
+                    issues.push(ValidationIssue::MissingChild { entry, child });
+                }
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Inserts a new entry under a fresh id, and returns that id.
+    pub fn add_entry(&mut self, text: String, parents: Vec<u128>) -> u128 {
+        let id = match self.entries.last_key_value() {
+            None => 0,
+            Some((&max, _)) => max
+                .checked_add(1)
+                .unwrap_or_else(|| (0..).find(|id| !self.entries.contains_key(id)).unwrap()),
+        };
+        for &parent in &parents {
+            self.children.entry(parent).or_default().insert(id);
+        }
+        let now = Some(Utc::now());
+        let entry = Entry {
+            text,
+            parents,
+            created: now,
+            modified: now,
+        };
+        self.entries.insert(id, entry);
+        id
+    }
+    /// Removes `id` along with every edge to and from it.
+    ///
+    /// Its children are kept, just without this parent.
+    pub fn remove_entry(&mut self, id: u128) -> Option<Entry> {
+        let entry = self.entries.remove(&id)?;
+        for child in self.children.remove(&id).unwrap_or_default() {
+            if let Some(child) = self.entries.get_mut(&child) {
+                child.parents.retain(|&p| p != id);
+                child.modified = Some(Utc::now());
+            }
+        }
+        for parent in &entry.parents {
+            if let Some(siblings) = self.children.get_mut(parent) {
+                siblings.remove(&id);
+                if siblings.is_empty() {
+                    self.children.remove(parent);
+                }
+            }
+        }
+        Some(entry)
+    }
+    /// Copies `root` and everything downstream of it under fresh ids, and returns the copy of `root`.
+    ///
+    /// Edges between the copied entries point at the copies. Parents outside of them are only
+    /// kept if `keep_external_parents` is set, otherwise the copy of `root` is a new root.
+    pub fn duplicate_subtree(&mut self, root: u128, keep_external_parents: bool) -> Option<u128> {
+        self.get(root)?;
+        let mut originals = self.descendants(root);
+        originals.insert(root);
+
+        let copies: BTreeMap<u128, u128> = originals
+            .into_iter()
+            .map(|id| {
+                let text = self.entries[&id].text.clone();
+                (id, self.add_entry(text, vec![]))
+            })
+            .collect();
+        for (&id, &copy) in &copies {
+            let parents: Vec<u128> = self.entries[&id]
+                .parents
+                .iter()
+                .filter_map(|p| match copies.get(p) {
+                    Some(&p) => Some(p),
+                    None => keep_external_parents.then_some(*p),
+                })
+                .collect();
+            for &parent in &parents {
+                self.children.entry(parent).or_default().insert(copy);
+            }
+            self.entries.get_mut(&copy).unwrap().parents = parents;
+        }
+        Some(copies[&root])
+    }
+    /// Makes `parent` a parent of `child`, unless it would close a cycle.
+    ///
+    /// Adding an existing edge again does nothing.
+    pub fn add_parent(&mut self, child: u128, parent: u128) -> Result<(), EdgeError> {
+        for id in [child, parent] {
+            if !self.entries.contains_key(&id) {
+                return Err(EdgeError::Missing(id));
+            }
+        }
+        if let Some(cycle) = self.shortest_path(child, parent, Direction::Down) {
+            return Err(EdgeError::Cycle(CycleError { cycle }));
+        }
+
+        let entry = self.entries.get_mut(&child).unwrap();
+        if !entry.parents.contains(&parent) {
+            entry.parents.push(parent);
+            entry.modified = Some(Utc::now());
+            self.children.entry(parent).or_default().insert(child);
+        }
+        Ok(())
+    }
+    /// Returns whether there was such an edge.
+    pub fn remove_parent(&mut self, child: u128, parent: u128) -> bool {
+        let removed = self.unlink(child, parent);
+        if removed {
+            self.entries.get_mut(&child).unwrap().modified = Some(Utc::now());
+        }
+        removed
+    }
+    /// [`Self::remove_parent`], without counting as a change to `child`.
+    fn unlink(&mut self, child: u128, parent: u128) -> bool {
+        let Some(entry) = self.entries.get_mut(&child) else {
+            return false;
+        };
+        let before = entry.parents.len();
+        entry.parents.retain(|&p| p != parent);
+        if entry.parents.len() == before {
+            return false;
+        }
+        if let Some(siblings) = self.children.get_mut(&parent) {
+            siblings.remove(&child);
+            if siblings.is_empty() {
+                self.children.remove(&parent);
+            }
+        }
+        true
+    }
+    pub fn set_text(&mut self, id: u128, text: String) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            if entry.text != text {
+                entry.text = text;
+                entry.modified = Some(Utc::now());
+            }
+        }
+    }
+
+    /// The graph in GraphViz DOT format, with edges pointing from parents to children.
+    pub fn to_dot(&self) -> String {
+        const MAX_LABEL: usize = 60;
+
+        let mut out = String::from("digraph {\n");
+        for (id, entry) in &self.entries {
+            let label = truncate(&entry.text, MAX_LABEL)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            out += &format!("    n{id} [label=\"{label}\"];\n");
+        }
+        for (id, entry) in &self.entries {
+            for parent in &entry.parents {
+                out += &format!("    n{parent} -> n{id};\n");
+            }
+        }
+        out += "}\n";
+        out
+    }
+
+    /// The graph as a Mermaid flowchart, with edges pointing from parents to children.
+    pub fn to_mermaid(&self, direction: FlowDirection) -> String {
+        const MAX_LABEL: usize = 60;
+
+        let direction = match direction {
+            FlowDirection::TopDown => "TD",
+            FlowDirection::BottomUp => "BT",
+            FlowDirection::LeftRight => "LR",
+            FlowDirection::RightLeft => "RL",
+        };
+        let mut out = format!("flowchart {direction}\n");
+        for (id, entry) in &self.entries {
+            let mut label = String::new();
+            for c in truncate(&entry.text, MAX_LABEL).chars() {
+                match c {
+                    '#' => label += "#35;",
+                    '"' => label += "#quot;",
+                    '<' => label += "#lt;",
+                    '>' => label += "#gt;",
+                    '\n' => label += "<br>",
+                    c => label.push(c),
+                }
+            }
+            out += &format!("    n{id}[\"{label}\"]\n");
+        }
+        for (id, entry) in &self.entries {
+            for parent in &entry.parents {
+                out += &format!("    n{parent} --> n{id}\n");
+            }
+        }
+        out
+    }
+
+    pub fn get(&self, id: u128) -> Option<&Entry> {
+        self.entries.get(&id)
+    }
+    pub fn entries(&self) -> &BTreeMap<u128, Entry> {
+        &self.entries
+    }
+    pub fn parents(&self, id: u128) -> impl Iterator<Item = u128> + '_ {
+        self.get(id).into_iter().flat_map(|e| &e.parents).copied()
+    }
+    pub fn children(&self, id: u128) -> impl Iterator<Item = u128> + '_ {
+        self.children.get(&id).into_iter().flatten().copied()
+    }
+
+    /// Everything upstream of `id`. Only includes `id` itself if it is part of a cycle.
+    pub fn ancestors(&self, id: u128) -> BTreeSet<u128> {
+        self.reachable(id, |id| self.parents(id))
+    }
+    /// Everything downstream of `id`. Only includes `id` itself if it is part of a cycle.
+    pub fn descendants(&self, id: u128) -> BTreeSet<u128> {
+        self.reachable(id, |id| self.children(id))
+    }
+    /// `id` with everything upstream and downstream of it.
+    pub fn lineage(&self, id: u128) -> BTreeSet<u128> {
+        let mut ids = self.ancestors(id);
+        ids.extend(self.descendants(id));
+        ids.insert(id);
+        ids
+    }
+    /// Only the entries in `ids`, dropping any edges to the rest.
+    pub fn subgraph(&self, ids: &BTreeSet<u128>) -> Self {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|(id, _)| ids.contains(id))
+            .map(|(&id, entry)| {
+                let mut entry = entry.clone();
+                entry.parents.retain(|p| ids.contains(p));
+                (id, entry)
+            })
+            .collect();
+        Self::from_raw(entries)
+    }
+    /// What changed going from `self` to `other`.
+    pub fn diff(&self, other: &Data) -> DataDiff {
+        let ids = |data: &Data| data.entries.keys().copied().collect::<BTreeSet<_>>();
+        let edges = |data: &Data| {
+            data.entries
+                .iter()
+                .flat_map(|(&id, entry)| entry.parents.iter().map(move |&p| (p, id)))
+                .collect::<BTreeSet<_>>()
+        };
+        let (before, after) = (ids(self), ids(other));
+        let (edges_before, edges_after) = (edges(self), edges(other));
+
+        DataDiff {
+            added: after.difference(&before).copied().collect(),
+            removed: before.difference(&after).copied().collect(),
+            edited: before
+                .intersection(&after)
+                .copied()
+                .filter(|&id| self.entries[&id].text != other.entries[&id].text)
+                .collect(),
+            added_edges: edges_after.difference(&edges_before).copied().collect(),
+            removed_edges: edges_before.difference(&edges_after).copied().collect(),
+        }
+    }
+    /// The shortest path from `from` to `to`, including both ends.
+    pub fn shortest_path(&self, from: u128, to: u128, direction: Direction) -> Option<Vec<u128>> {
+        self.get(from)?;
+        self.get(to)?;
+
+        let mut previous: BTreeMap<u128, u128> = BTreeMap::new();
+        let mut pending = VecDeque::from([from]);
+        while let Some(id) = pending.pop_front() {
+            if id == to {
+                let mut path = vec![to];
+                while let Some(&p) = previous.get(path.last().unwrap()) {
+                    path.push(p);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.neighbours(id, direction) {
+                if next != from && !previous.contains_key(&next) {
+                    previous.insert(next, id);
+                    pending.push_back(next);
+                }
+            }
+        }
+        None
+    }
+    fn neighbours(&self, id: u128, direction: Direction) -> impl Iterator<Item = u128> + '_ {
+        let up = matches!(direction, Direction::Up | Direction::Both).then(|| self.parents(id));
+        let down =
+            matches!(direction, Direction::Down | Direction::Both).then(|| self.children(id));
+        up.into_iter().flatten().chain(down.into_iter().flatten())
+    }
+    fn reachable<I: Iterator<Item = u128>>(
+        &self,
+        id: u128,
+        next: impl Fn(u128) -> I,
+    ) -> BTreeSet<u128> {
+        let mut done = BTreeSet::new();
+        let mut pending: Vec<u128> = next(id).collect();
+        while let Some(id) = pending.pop() {
+            if done.insert(id) {
+                pending.extend(next(id));
+            }
+        }
+        done
+    }
+
+    /// Entries without parents, sorted.
+    pub fn roots(&self) -> Vec<u128> {
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.parents.is_empty())
+            .map(|(&id, _)| id)
+            .collect()
+    }
+    /// Entries without children, sorted.
+    pub fn leaves(&self) -> Vec<u128> {
+        self.entries
+            .keys()
+            .copied()
+            .filter(|&id| self.children(id).next().is_none())
+            .collect()
+    }
+
+    /// Cycles in the graph, each listed from parent to child.
+    ///
+    /// This reports one cycle per back edge of a depth-first search,
+    /// so overlapping cycles may not all be listed.
+    pub fn find_cycles(&self) -> Vec<Vec<u128>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Color {
+            Grey,
+            Black,
+        }
+        fn visit(
+            data: &Data,
+            id: u128,
+            colors: &mut BTreeMap<u128, Color>,
+            path: &mut Vec<u128>,
+            cycles: &mut Vec<Vec<u128>>,
+        ) {
+            colors.insert(id, Color::Grey);
+            path.push(id);
+            for child in data.children(id) {
+                match colors.get(&child) {
+                    None => visit(data, child, colors, path, cycles),
+                    Some(Color::Grey) => {
+                        let start = path.iter().position(|&p| p == child).unwrap();
+                        cycles.push(path[start..].to_vec());
+                    }
+                    Some(Color::Black) => {}
+                }
+            }
+            path.pop();
+            colors.insert(id, Color::Black);
+        }
+
+        let mut colors = BTreeMap::new();
+        let mut cycles = vec![];
+        for &id in self.entries.keys() {
+            if !colors.contains_key(&id) {
+                visit(self, id, &mut colors, &mut vec![], &mut cycles);
+            }
+        }
+        cycles
+    }
+
+    /// Groups of entries that can all reach each other, sorted.
+    ///
+    /// Each cycle, or set of overlapping cycles, is one group.
+    /// Entries that aren't part of any cycle come back on their own.
+    pub fn strongly_connected_components(&self) -> Vec<BTreeSet<u128>> {
+        // Tarjan's algorithm.
+        #[derive(Default)]
+        struct State {
+            next_index: usize,
+            indices: BTreeMap<u128, usize>,
+            lowlinks: BTreeMap<u128, usize>,
+            stack: Vec<u128>,
+            on_stack: BTreeSet<u128>,
+            components: Vec<BTreeSet<u128>>,
+        }
+        fn visit(data: &Data, id: u128, state: &mut State) {
+            state.indices.insert(id, state.next_index);
+            state.lowlinks.insert(id, state.next_index);
+            state.next_index += 1;
+            state.stack.push(id);
+            state.on_stack.insert(id);
+
+            for child in data.children(id) {
+                if !data.entries.contains_key(&child) {
+                    continue;
+                }
+                let lowlink = if !state.indices.contains_key(&child) {
+                    visit(data, child, state);
+                    state.lowlinks[&child]
+                } else if state.on_stack.contains(&child) {
+                    state.indices[&child]
+                } else {
+                    continue;
+                };
+                let own = state.lowlinks.get_mut(&id).unwrap();
+                *own = (*own).min(lowlink);
+            }
+
+            if state.lowlinks[&id] == state.indices[&id] {
+                let mut component = BTreeSet::new();
+                while let Some(member) = state.stack.pop() {
+                    state.on_stack.remove(&member);
+                    component.insert(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                state.components.push(component);
+            }
+        }
+
+        let mut state = State::default();
+        for &id in self.entries.keys() {
+            if !state.indices.contains_key(&id) {
+                visit(self, id, &mut state);
+            }
+        }
+        state.components.sort();
+        state.components
+    }
+
+    /// Ids grouped by depth, the longest path to them from a root.
+    pub fn layers(&self) -> Result<Vec<Vec<u128>>, CycleError> {
+        let mut depths: BTreeMap<u128, usize> = BTreeMap::new();
+        let mut layers: Vec<Vec<u128>> = vec![];
+        for id in self.topological_order()? {
+            let depth = self
+                .parents(id)
+                .filter_map(|p| Some(depths.get(&p)? + 1))
+                .max()
+                .unwrap_or(0);
+            depths.insert(id, depth);
+            if layers.len() <= depth {
+                layers.resize_with(depth + 1, Vec::new);
+            }
+            layers[depth].push(id);
+        }
+        Ok(layers)
+    }
+    /// All ids, with parents always before their children.
+    pub fn topological_order(&self) -> Result<Vec<u128>, CycleError> {
+        let mut indegrees: BTreeMap<u128, usize> = self
+            .entries
+            .iter()
+            .map(|(&id, entry)| {
+                let parents: BTreeSet<_> = entry
+                    .parents
+                    .iter()
+                    .filter(|p| self.entries.contains_key(p))
+                    .collect();
+                (id, parents.len())
+            })
+            .collect();
+        let mut ready: BTreeSet<u128> = indegrees
+            .iter()
+            .filter(|(_, &degree)| degree == 0)
+            .map(|(&id, _)| id)
+            .collect();
+
+        let mut order = Vec::with_capacity(self.entries.len());
+        while let Some(id) = ready.pop_first() {
+            order.push(id);
+            for child in self.children(id) {
+                let Some(degree) = indegrees.get_mut(&child) else {
+                    continue;
+                };
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert(child);
+                }
+            }
+        }
+
+        if order.len() == self.entries.len() {
+            Ok(order)
+        } else {
+            let cycle = self.find_cycles().swap_remove(0);
+            Err(CycleError { cycle })
+        }
+    }
+    /// The same graph without edges implied by longer paths,
+    /// like `1 -> 3` when there is already `1 -> 2 -> 3`.
+    pub fn transitive_reduction(&self) -> Result<Self, CycleError> {
+        self.topological_order()?;
+
+        let mut reduced = self.clone();
+        for (&id, children) in &self.children {
+            let implied: BTreeSet<u128> = children
+                .iter()
+                .flat_map(|&child| self.descendants(child))
+                .collect();
+            for &child in children.intersection(&implied) {
+                reduced.unlink(child, id);
+            }
+        }
+        Ok(reduced)
+    }
+}
+
+/// Cuts `text` to at most `max` characters, marking where it was cut.
+pub fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_owned(),
+    }
+}
+
+const STORAGE_KEY: &str = "locallyvalid-data";
+
+pub(crate) fn local_storage() -> Option<web_sys::Storage> {
+    match leptos::window().local_storage() {
+        Ok(storage) => storage,
+        Err(e) => {
+            log::warn!("Local storage is unavailable: {e:?}");
+            None
+        }
+    }
+}
+
+/// The changes between two versions of the same graph, see [`Data::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DataDiff {
+    pub added: BTreeSet<u128>,
+    pub removed: BTreeSet<u128>,
+    /// Entries in both versions, with different text.
+    pub edited: BTreeSet<u128>,
+    /// As `(parent, child)` pairs.
+    pub added_edges: BTreeSet<(u128, u128)>,
+    /// As `(parent, child)` pairs.
+    pub removed_edges: BTreeSet<(u128, u128)>,
+}
+impl DataDiff {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards parents.
+    Up,
+    /// Towards children.
+    Down,
+    /// Along any edge.
+    Both,
+}
+
+/// The layout direction of a Mermaid flowchart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlowDirection {
+    #[default]
+    TopDown,
+    BottomUp,
+    LeftRight,
+    RightLeft,
+}
+
+#[derive(Debug)]
+pub enum DataError {
+    Json(serde_json::Error),
+    Invalid(Vec<ValidationIssue>),
+    /// The ids along a cycle, in order.
+    Cycle(Vec<u128>),
+}
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid JSON: {e}"),
+            Self::Invalid(issues) => {
+                let issues: Vec<_> = issues.iter().map(|i| i.to_string()).collect();
+                write!(f, "{}", issues.join("\n"))
+            }
+            Self::Cycle(cycle) => CycleError {
+                cycle: cycle.clone(),
+            }
+            .fmt(f),
+        }
+    }
+}
+impl Error for DataError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            Self::Invalid(_) | Self::Cycle(_) => None,
+        }
+    }
+}
+impl From<serde_json::Error> for DataError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// `entry` lists `parent` as a parent, but there is no such entry.
+    MissingParent { entry: u128, parent: u128 },
+    /// `entry` has `child` as a child, but there is no such entry.
+    MissingChild { entry: u128, child: u128 },
+}
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingParent { entry, parent } => {
+                write!(f, "entry {entry} refers to missing parent {parent}")
+            }
+            Self::MissingChild { entry, child } => {
+                write!(f, "entry {entry} refers to missing child {child}")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdgeError {
+    /// There is no entry with this id.
+    Missing(u128),
+    /// The edge would close this cycle.
+    Cycle(CycleError),
+}
+impl fmt::Display for EdgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(id) => write!(f, "no entry with id {id}"),
+            Self::Cycle(e) => e.fmt(f),
+        }
+    }
+}
+impl Error for EdgeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// One of the cycles in the graph, from parent to child.
+    pub cycle: Vec<u128>,
+}
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids: Vec<_> = self.cycle.iter().map(|id| id.to_string()).collect();
+        write!(f, "cycle between entries {}", ids.join(" -> "))
+    }
+}
+impl Error for CycleError {}
+impl From<CycleError> for DataError {
+    fn from(e: CycleError) -> Self {
+        Self::Cycle(e.cycle)
+    }
+}
+
+/// `u128` ids don't fit in a JSON number, so they are written as decimal strings.
+/// Plain numbers are still accepted when reading, for older files.
+mod decimal {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::{collections::BTreeMap, fmt};
+
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct Id(u128);
+
+    impl Serialize for Id {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&self.0)
+        }
+    }
+    impl<'de> Deserialize<'de> for Id {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct Visitor;
+            impl<'de> de::Visitor<'de> for Visitor {
+                type Value = Id;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a non-negative integer or a decimal string")
+                }
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Id, E> {
+                    v.parse().map(Id).map_err(E::custom)
+                }
+                fn visit_u64<E: de::Error>(self, v: u64) -> Result<Id, E> {
+                    Ok(Id(v.into()))
+                }
+                fn visit_u128<E: de::Error>(self, v: u128) -> Result<Id, E> {
+                    Ok(Id(v))
+                }
+            }
+            deserializer.deserialize_any(Visitor)
+        }
+    }
+
+    pub mod vec {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(ids: &[u128], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(ids.iter().map(|&id| Id(id)))
+        }
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<u128>, D::Error> {
+            let ids = Vec::<Id>::deserialize(deserializer)?;
+            Ok(ids.into_iter().map(|Id(id)| id).collect())
+        }
+    }
+
+    pub mod map {
+        use super::*;
+
+        pub fn serialize<S: Serializer, V: Serialize>(
+            map: &BTreeMap<u128, V>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(map.iter().map(|(&id, v)| (Id(id), v)))
+        }
+        pub fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+            deserializer: D,
+        ) -> Result<BTreeMap<u128, V>, D::Error> {
+            let map = BTreeMap::<Id, V>::deserialize(deserializer)?;
+            Ok(map.into_iter().map(|(Id(id), v)| (id, v)).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(entries: &[(u128, &[u128])]) -> Data {
+        Data::from_raw(
+            entries
+                .iter()
+                .map(|&(id, parents)| {
+                    let entry = Entry {
+                        text: id.to_string(),
+                        parents: parents.to_vec(),
+                        created: None,
+                        modified: None,
+                    };
+                    (id, entry)
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_json_round_trip_large_ids() {
+        let big = u128::from(u64::MAX) + 1;
+        let data = Data::from_raw(BTreeMap::from([
+            (
+                big,
+                Entry {
+                    text: "root".into(),
+                    parents: vec![],
+                    created: None,
+                    modified: None,
+                },
+            ),
+            (
+                u128::MAX,
+                Entry {
+                    text: "child".into(),
+                    parents: vec![big],
+                    created: None,
+                    modified: None,
+                },
+            ),
+        ]));
+
+        let json = data.to_json();
+        assert!(json.contains(&format!("\"{}\"", u128::MAX)));
+        assert_eq!(Data::from_json(&json).unwrap(), data);
+    }
+
+    #[test]
+    fn test_json_numeric_ids() {
+        let data = Data::from_json(
+            r#"{"1": {"text": "a", "parents": []}, "2": {"text": "b", "parents": [1]}}"#,
+        )
+        .unwrap();
+        assert_eq!(data.get(2).unwrap().parents, vec![1]);
+        assert_eq!(data.children(1).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn test_from_json_errors() {
+        let err = Data::from_json("{").unwrap_err();
+        assert!(matches!(err, DataError::Json(_)));
+
+        let err = Data::from_json(
+            r#"{"1": {"text": "a", "parents": [2, 3]}, "4": {"text": "b", "parents": [3]}}"#,
+        )
+        .unwrap_err();
+        let DataError::Invalid(issues) = &err else {
+            panic!("{err:?}")
+        };
+        assert_eq!(
+            issues,
+            &[
+                ValidationIssue::MissingParent {
+                    entry: 1,
+                    parent: 2
+                },
+                ValidationIssue::MissingParent {
+                    entry: 1,
+                    parent: 3
+                },
+                ValidationIssue::MissingParent {
+                    entry: 4,
+                    parent: 3
+                },
+            ]
+        );
+        assert_eq!(
+            err.to_string().lines().next(),
+            Some("entry 1 refers to missing parent 2")
+        );
+
+        let err = Data::from_json(
+            r#"{"1": {"text": "a", "parents": [2]}, "2": {"text": "b", "parents": [1]}}"#,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, DataError::Cycle(ref cycle) if cycle.len() == 2),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let data = graph(&[(1, &[3]), (2, &[1]), (3, &[2]), (4, &[1])]);
+        assert_eq!(data.find_cycles(), vec![vec![1, 2, 3]]);
+
+        let data = graph(&[(1, &[]), (2, &[1]), (3, &[1, 2])]);
+        assert_eq!(data.find_cycles(), Vec::<Vec<u128>>::new());
+
+        let data = graph(&[(1, &[1])]);
+        assert_eq!(data.find_cycles(), vec![vec![1]]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // Two cycles, 1-2-3 and 5-6, with 4 and 7 hanging off them.
+        let data = graph(&[
+            (1, &[3]),
+            (2, &[1]),
+            (3, &[2]),
+            (4, &[3]),
+            (5, &[6, 4]),
+            (6, &[5]),
+            (7, &[6]),
+        ]);
+        assert_eq!(
+            data.strongly_connected_components(),
+            vec![
+                BTreeSet::from([1, 2, 3]),
+                BTreeSet::from([4]),
+                BTreeSet::from([5, 6]),
+                BTreeSet::from([7]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let data = graph(&[(1, &[2, 3]), (2, &[4]), (3, &[4]), (4, &[]), (5, &[1, 1])]);
+        assert_eq!(data.topological_order(), Ok(vec![4, 2, 3, 1, 5]));
+
+        let data = graph(&[(1, &[]), (2, &[1, 4]), (3, &[2]), (4, &[3])]);
+        assert_eq!(
+            data.topological_order(),
+            Err(CycleError {
+                cycle: vec![2, 3, 4]
+            })
+        );
+    }
+
+    #[test]
+    fn test_layers() {
+        // 4 is two steps from 1 through 2, despite also being a child of the root 5.
+        let data = graph(&[(1, &[]), (2, &[1]), (3, &[1]), (4, &[2, 5]), (5, &[])]);
+        assert_eq!(data.layers(), Ok(vec![vec![1, 5], vec![2, 3], vec![4]]));
+
+        let data = graph(&[(1, &[2]), (2, &[1])]);
+        assert!(data.layers().is_err());
+    }
+
+    #[test]
+    fn test_transitive_reduction() {
+        let data = graph(&[(1, &[]), (2, &[1]), (3, &[1, 2])]);
+        let reduced = data.transitive_reduction().unwrap();
+        assert_eq!(reduced, graph(&[(1, &[]), (2, &[1]), (3, &[2])]));
+        assert_eq!(reduced.transitive_reduction(), Ok(reduced));
+
+        let data = graph(&[(1, &[2]), (2, &[1])]);
+        assert!(data.transitive_reduction().is_err());
+    }
+
+    #[test]
+    fn test_roots_and_leaves() {
+        let data = graph(&[(1, &[]), (2, &[1]), (3, &[1]), (4, &[]), (5, &[2, 4])]);
+        assert_eq!(data.roots(), vec![1, 4]);
+        assert_eq!(data.leaves(), vec![3, 5]);
+    }
+
+    #[test]
+    fn test_ancestors_and_descendants() {
+        // 1 is the shared ancestor of 4, through both 2 and 3.
+        let data = graph(&[(1, &[]), (2, &[1]), (3, &[1]), (4, &[2, 3]), (5, &[])]);
+        assert_eq!(data.ancestors(4), BTreeSet::from([1, 2, 3]));
+        assert_eq!(data.ancestors(1), BTreeSet::new());
+        assert_eq!(data.descendants(1), BTreeSet::from([2, 3, 4]));
+        assert_eq!(data.descendants(2), BTreeSet::from([4]));
+
+        let data = graph(&[(1, &[3]), (2, &[1]), (3, &[2])]);
+        assert_eq!(data.ancestors(1), BTreeSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn test_lineage_subgraph() {
+        // 3 is a sibling of 2, so unrelated to it.
+        let data = graph(&[(1, &[]), (2, &[1]), (3, &[1]), (4, &[2, 3]), (5, &[])]);
+        assert_eq!(data.lineage(2), BTreeSet::from([1, 2, 4]));
+
+        let focused = data.subgraph(&data.lineage(2));
+        assert_eq!(focused.validate(), Ok(()));
+        assert_eq!(focused, graph(&[(1, &[]), (2, &[1]), (4, &[2])]));
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = graph(&[(1, &[]), (2, &[1]), (3, &[1, 2])]);
+        assert!(before.diff(&before).is_empty());
+
+        let mut after = before.clone();
+        let added = after.add_entry("New".into(), vec![3]);
+        assert_eq!(
+            before.diff(&after),
+            DataDiff {
+                added: BTreeSet::from([added]),
+                added_edges: BTreeSet::from([(3, added)]),
+                ..DataDiff::default()
+            }
+        );
+
+        let mut after = before.clone();
+        after.remove_parent(3, 1);
+        after.set_text(2, "Edited".into());
+        assert_eq!(
+            before.diff(&after),
+            DataDiff {
+                edited: BTreeSet::from([2]),
+                removed_edges: BTreeSet::from([(1, 3)]),
+                ..DataDiff::default()
+            }
+        );
+
+        // Removing an entry also removes its edges.
+        let mut after = before.clone();
+        after.remove_entry(2);
+        assert_eq!(
+            before.diff(&after),
+            DataDiff {
+                removed: BTreeSet::from([2]),
+                removed_edges: BTreeSet::from([(1, 2), (2, 3)]),
+                ..DataDiff::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let data = graph(&[
+            (1, &[]),
+            (2, &[1]),
+            (3, &[2]),
+            (4, &[1, 3]),
+            (5, &[1]),
+            (6, &[7]),
+            (7, &[6]),
+        ]);
+        assert_eq!(data.shortest_path(4, 1, Direction::Up), Some(vec![4, 1]));
+        assert_eq!(data.shortest_path(3, 1, Direction::Up), Some(vec![3, 2, 1]));
+        assert_eq!(data.shortest_path(1, 3, Direction::Up), None);
+        assert_eq!(
+            data.shortest_path(1, 3, Direction::Down),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(data.shortest_path(5, 4, Direction::Down), None);
+        assert_eq!(
+            data.shortest_path(5, 4, Direction::Both),
+            Some(vec![5, 1, 4])
+        );
+        assert_eq!(data.shortest_path(2, 2, Direction::Up), Some(vec![2]));
+
+        // Cycles terminate.
+        assert_eq!(data.shortest_path(6, 1, Direction::Both), None);
+        assert_eq!(data.shortest_path(6, 7, Direction::Down), Some(vec![6, 7]));
+    }
+
+    #[test]
+    fn test_add_entry() {
+        let mut data = graph(&[(1, &[]), (5, &[1])]);
+        let id = data.add_entry("new".into(), vec![1, 5]);
+        assert_eq!(id, 6);
+        assert_eq!(data.get(6).unwrap().text, "new");
+        assert_eq!(data.children(1).collect::<Vec<_>>(), vec![5, 6]);
+        assert_eq!(data.children(5).collect::<Vec<_>>(), vec![6]);
+
+        let mut data = graph(&[(0, &[]), (u128::MAX, &[])]);
+        assert_eq!(data.add_entry(String::new(), vec![]), 1);
+
+        assert_eq!(Data::default().add_entry(String::new(), vec![]), 0);
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let mut data = graph(&[(1, &[]), (2, &[])]);
+        let id = data.add_entry("new".into(), vec![1]);
+        let entry = data.get(id).unwrap();
+        assert!(entry.created.is_some());
+        assert_eq!(entry.modified, entry.created);
+
+        // Unchanged text doesn't count as an edit.
+        data.set_text(1, "1".into());
+        assert_eq!(data.get(1).unwrap().modified, None);
+        data.set_text(1, "one".into());
+        assert!(data.get(1).unwrap().modified.is_some());
+
+        data.add_parent(2, 1).unwrap();
+        assert!(data.get(2).unwrap().modified.is_some());
+
+        assert_eq!(Data::from_json(&data.to_json()).unwrap(), data);
+        assert!(!graph(&[(1, &[])]).to_json().contains("created"));
+    }
+
+    #[test]
+    fn test_remove_entry() {
+        let mut data = graph(&[(1, &[]), (2, &[1]), (3, &[2]), (4, &[1, 2])]);
+        let removed = data.remove_entry(2).unwrap();
+        assert_eq!(removed.parents, vec![1]);
+
+        assert_eq!(data.validate(), Ok(()));
+        assert!(data.get(2).is_none());
+        assert_eq!(data.get(3).unwrap().parents, Vec::<u128>::new());
+        assert_eq!(data.get(4).unwrap().parents, vec![1]);
+        assert_eq!(data.children(1).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(data.children(2).count(), 0);
+        assert!(data
+            .diff(&graph(&[(1, &[]), (3, &[]), (4, &[1])]))
+            .is_empty());
+
+        assert_eq!(data.remove_entry(2), None);
+    }
+
+    #[test]
+    fn test_duplicate_subtree() {
+        // 3 also has a parent outside of the copied subtree.
+        let mut data = graph(&[(1, &[]), (2, &[1]), (3, &[2, 4]), (4, &[])]);
+        assert_eq!(data.duplicate_subtree(2, true), Some(5));
+        assert_eq!(data.validate(), Ok(()));
+        assert_eq!(data.get(5).unwrap().parents, vec![1]);
+        assert_eq!(data.get(6).unwrap().parents, vec![5, 4]);
+        assert_eq!(data.get(6).unwrap().text, "3");
+
+        assert_eq!(data.duplicate_subtree(2, false), Some(7));
+        assert_eq!(data.get(7).unwrap().parents, vec![]);
+        assert_eq!(data.get(8).unwrap().parents, vec![7]);
+
+        // Cycles are copied once.
+        let mut data = graph(&[(1, &[2]), (2, &[1])]);
+        assert_eq!(data.duplicate_subtree(1, false), Some(3));
+        assert_eq!(data.get(3).unwrap().parents, vec![4]);
+        assert_eq!(data.get(4).unwrap().parents, vec![3]);
+        assert_eq!(data.entries().len(), 4);
+
+        assert_eq!(data.duplicate_subtree(10, true), None);
+    }
+
+    #[test]
+    fn test_add_and_remove_parent() {
+        let mut data = graph(&[(1, &[]), (2, &[1]), (3, &[2]), (4, &[])]);
+
+        assert_eq!(data.add_parent(4, 1), Ok(()));
+        assert_eq!(data.add_parent(4, 1), Ok(()));
+        assert_eq!(data.get(4).unwrap().parents, vec![1]);
+        assert_eq!(data.children(1).collect::<Vec<_>>(), vec![2, 4]);
+
+        assert_eq!(data.add_parent(4, 9), Err(EdgeError::Missing(9)));
+        assert_eq!(
+            data.add_parent(1, 3),
+            Err(EdgeError::Cycle(CycleError {
+                cycle: vec![1, 2, 3]
+            }))
+        );
+        assert_eq!(
+            data.add_parent(2, 2),
+            Err(EdgeError::Cycle(CycleError { cycle: vec![2] }))
+        );
+
+        assert!(data.remove_parent(4, 1));
+        assert!(!data.remove_parent(4, 1));
+        let original = graph(&[(1, &[]), (2, &[1]), (3, &[2]), (4, &[])]);
+        assert!(data.diff(&original).is_empty());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut data = graph(&[(1, &[]), (2, &[1]), (3, &[1, 2])]);
+        data.set_text(1, "A \"quoted\" \\ root".into());
+        data.set_text(2, "Two\nlines".into());
+        data.set_text(3, "x".repeat(70));
+
+        let expected = r#"digraph {
+    n1 [label="A \"quoted\" \\ root"];
+    n2 [label="Two\nlines"];
+    n3 [label="xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx…"];
+    n1 -> n2;
+    n1 -> n3;
+    n2 -> n3;
+}
+"#;
+        assert_eq!(data.to_dot(), expected);
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mut data = graph(&[(1, &[]), (2, &[1])]);
+        data.set_text(1, "A \"quoted\" <root> #1".into());
+        data.set_text(2, "Two\nlines".into());
+
+        let expected = r#"flowchart TD
+    n1["A #quot;quoted#quot; #lt;root#gt; #35;1"]
+    n2["Two<br>lines"]
+    n1 --> n2
+"#;
+        assert_eq!(data.to_mermaid(FlowDirection::default()), expected);
+        assert!(data
+            .to_mermaid(FlowDirection::LeftRight)
+            .starts_with("flowchart LR\n"));
+    }
+}
@@ -0,0 +1,117 @@
+use std::ops::Range;
+
+/// Matched characters at the start of a word score this much more.
+const WORD_START: i64 = 2;
+/// Matched characters right after the previous match score this much more.
+const CONSECUTIVE: i64 = 3;
+/// Every gap between matched characters costs this much, regardless of its length.
+const GAP: i64 = 1;
+
+/// Where the characters of a query were found in some text, see [`fuzzy_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better.
+    pub score: i64,
+    /// Byte ranges of the matched characters, with adjacent ones merged.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Finds the characters of `query` in `text` in order, but possibly with gaps between them.
+/// Case is ignored.
+///
+/// Runs of consecutive characters and matches at the start of words are preferred,
+/// so `"lv"` matches `"locally valid"` better than `"lovely"`.
+pub fn fuzzy_match(text: &str, query: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let first = *query.first()?;
+
+    // Matching greedily from every word start is good enough, and keeps this linear per start.
+    let mut best = match_from(&chars, &query, 0)?;
+    for start in 1..chars.len() {
+        if !is_word_start(&chars, start) || !same(chars[start].1, first) {
+            continue;
+        }
+        match match_from(&chars, &query, start) {
+            Some(found) if found.score > best.score => best = found,
+            Some(_) => {}
+            None => break, // Starting later can't help.
+        }
+    }
+    Some(best)
+}
+
+fn match_from(chars: &[(usize, char)], query: &[char], start: usize) -> Option<FuzzyMatch> {
+    let mut score = 0;
+    let mut ranges: Vec<Range<usize>> = vec![];
+    let mut previous = None;
+    let mut query = query.iter().peekable();
+    for (i, &(byte, c)) in chars.iter().enumerate().skip(start) {
+        let Some(&&next) = query.peek() else {
+            break;
+        };
+        if !same(c, next) {
+            continue;
+        }
+        query.next();
+
+        score += 1;
+        if is_word_start(chars, i) {
+            score += WORD_START;
+        }
+        let end = byte + c.len_utf8();
+        match (previous, ranges.last_mut()) {
+            (Some(p), Some(last)) if p + 1 == i => {
+                score += CONSECUTIVE;
+                last.end = end;
+            }
+            (Some(_), _) => {
+                score -= GAP;
+                ranges.push(byte..end);
+            }
+            (None, _) => ranges.push(byte..end),
+        }
+        previous = Some(i);
+    }
+    query
+        .peek()
+        .is_none()
+        .then_some(FuzzyMatch { score, ranges })
+}
+
+fn is_word_start(chars: &[(usize, char)], i: usize) -> bool {
+    i == 0 || !chars[i - 1].1.is_alphanumeric()
+}
+/// Whether `c` is `lower`, ignoring case.
+fn same(c: char, lower: char) -> bool {
+    c.to_lowercase().next() == Some(lower)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        let found = fuzzy_match("Hello world", "hw").unwrap();
+        assert_eq!(found.ranges, vec![0..1, 6..7]);
+
+        assert_eq!(fuzzy_match("Hello world", "wh"), None);
+        assert_eq!(fuzzy_match("Hello world", ""), None);
+
+        // Case and multi-byte characters.
+        let found = fuzzy_match("Ünïcode", "ÜC").unwrap();
+        assert_eq!(found.ranges, vec![0..2, 5..6]);
+    }
+
+    #[test]
+    fn test_fuzzy_match_prefers_runs_and_word_starts() {
+        // The greedy match from the start would be `a_b`.
+        let found = fuzzy_match("a_b ab", "ab").unwrap();
+        assert_eq!(found.ranges, vec![4..6]);
+
+        let score = |text| fuzzy_match(text, "lv").unwrap().score;
+        assert!(score("locally valid") > score("lovely"));
+        assert!(score("lv") > score("locally valid"));
+    }
+}
@@ -68,6 +68,30 @@ const SI_PREFIXES: [SIPrefix; NUMBER_OF_PREFIXES] = [
     SIPrefix { name: "quecto", symbol: "q",  exp: -30,  adoption: 2022 },
 ];
 
+#[derive(Debug, Clone, Copy)]
+struct BinaryPrefix {
+    #[allow(dead_code)]
+    name: &'static str,
+    symbol: &'static str,
+    /// Power of 1024.
+    exp: u8,
+}
+
+/// IEC prefixes, for sizes that come in powers of two like bytes.
+#[rustfmt::skip]
+const BINARY_PREFIXES: [BinaryPrefix; 10] = [
+    BinaryPrefix { name: "quebi", symbol: "Qi", exp: 10 },
+    BinaryPrefix { name: "robi",  symbol: "Ri", exp: 9 },
+    BinaryPrefix { name: "yobi",  symbol: "Yi", exp: 8 },
+    BinaryPrefix { name: "zebi",  symbol: "Zi", exp: 7 },
+    BinaryPrefix { name: "exbi",  symbol: "Ei", exp: 6 },
+    BinaryPrefix { name: "pebi",  symbol: "Pi", exp: 5 },
+    BinaryPrefix { name: "tebi",  symbol: "Ti", exp: 4 },
+    BinaryPrefix { name: "gibi",  symbol: "Gi", exp: 3 },
+    BinaryPrefix { name: "mebi",  symbol: "Mi", exp: 2 },
+    BinaryPrefix { name: "kibi",  symbol: "Ki", exp: 1 },
+];
+
 const SUPERSCRIPTS: [(char, char); 12] = [
     ('0', '⁰'),
     ('1', '¹'),
@@ -100,30 +124,156 @@ pub fn prefix_datapoints() -> [super::Datapoint; NUMBER_OF_PREFIXES] {
     )
 }
 
-pub fn round_with_scaled_unit(number: f64, unit: &str) -> String {
-    let (symbol, scaled_number): (&str, f64) = match pick_prefix(number) {
-        Some(prefix) => (prefix.symbol, number / 10_f64.powi(prefix.exp.into())),
-        None => ("", number),
+/// How many significant digits the `*_default` formatters keep.
+pub const DEFAULT_DIGITS: u8 = 3;
+
+/// How to round away the digits that don't fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Ties go away from zero.
+    HalfUp,
+    /// Ties go to the even digit, like `format!` does.
+    #[default]
+    HalfEven,
+    /// Towards zero.
+    Truncate,
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards negative infinity.
+    Floor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Significant digits to keep, integer digits are never rounded away.
+    pub digits: u8,
+    pub rounding: RoundingMode,
+}
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            digits: DEFAULT_DIGITS,
+            rounding: RoundingMode::default(),
+        }
+    }
+}
+impl FormatOptions {
+    pub fn digits(digits: u8) -> Self {
+        Self {
+            digits,
+            ..Self::default()
+        }
+    }
+    pub fn rounding(self, rounding: RoundingMode) -> Self {
+        Self { rounding, ..self }
+    }
+}
+
+pub fn round_with_scaled_unit_default(number: f64, unit: &str) -> String {
+    round_with_scaled_unit(number, unit, FormatOptions::default())
+}
+pub fn round_with_scaled_unit(number: f64, unit: &str, options: FormatOptions) -> String {
+    if let Some(special) = non_finite(number) {
+        return special.to_owned();
+    }
+    let (prefix, rounded_number) = round_with_prefix(number, options);
+    let symbol = prefix.map_or("", |prefix| prefix.symbol);
+
+    format!("{rounded_number}{symbol}{unit}")
+}
+
+/// Formats a datapoint as `1.23 ± 0.04 kg`, with both numbers sharing the same prefix.
+///
+/// The uncertainty keeps one significant digit, or two if it starts with a 1 or 2, and the value
+/// is rounded to match. Without an uncertainty this is just [`round_with_scaled_unit_default`].
+pub fn format_with_uncertainty(datapoint: &super::Datapoint, unit: &str) -> String {
+    let number = datapoint.size;
+    let uncertainty = datapoint
+        .standard_uncertainty
+        .filter(|u| u.is_finite() && *u > 0.);
+    let (Some(uncertainty), None) = (uncertainty, non_finite(number)) else {
+        return round_with_scaled_unit_default(number, unit);
     };
-    let rounded_number = round_to_three_significant_digits(scaled_number);
+
+    let (symbol, scale): (&str, f64) = match pick_prefix(number) {
+        Some(prefix) => (prefix.symbol, 10_f64.powi(prefix.exp.into())),
+        None => ("", 1.),
+    };
+    let (number, uncertainty) = (number / scale, uncertainty / scale);
+
+    let magnitude = uncertainty.log10().floor() as i32;
+    // Rounding a leading 1 or 2 to one digit would change it too much.
+    let figures = if uncertainty / 10_f64.powi(magnitude) < 3. {
+        2
+    } else {
+        1
+    };
+    let decimals = figures - 1 - magnitude;
+
+    let number = round_to_place(number, decimals, RoundingMode::default());
+    let uncertainty = round_to_place(uncertainty, decimals, RoundingMode::default());
+    format!("{number} ± {uncertainty} {symbol}{unit}")
+        .trim_end()
+        .to_owned()
+}
+
+/// Compact counts like `1.2k` or `3.4M`, without a unit and never below a thousand.
+///
+/// Rounds down, so nothing is shown as more than it is.
+pub fn format_count(n: u64) -> String {
+    let number = n as f64;
+    match pick_prefix(number) {
+        Some(prefix) if n >= 1000 => {
+            let options = FormatOptions::default().rounding(RoundingMode::Truncate);
+            let scaled_number = number / 10_f64.powi(prefix.exp.into());
+            let rounded_number = round_to_significant_digits(scaled_number, options);
+            format!("{rounded_number}{}", prefix.symbol)
+        }
+        _ => n.to_string(),
+    }
+}
+
+/// Like [`round_with_scaled_unit`], but in powers of 1024: `1536` bytes is `1.5KiB`.
+pub fn round_with_binary_prefix(number: f64, unit: &str) -> String {
+    if let Some(special) = non_finite(number) {
+        return special.to_owned();
+    }
+    let round = |prefix: Option<&BinaryPrefix>| {
+        let scale = prefix.map_or(1., |prefix| 1024_f64.powi(prefix.exp.into()));
+        round_to_significant_digits(number / scale, FormatOptions::default())
+    };
+    let mut prefix = pick_binary_prefix(number);
+    let mut rounded_number = round(prefix);
+    if reaches(&rounded_number, 1024.) {
+        let exp = prefix.map_or(0, |prefix| prefix.exp) + 1;
+        if let Some(next) = BINARY_PREFIXES.iter().find(|prefix| prefix.exp == exp) {
+            prefix = Some(next);
+            rounded_number = round(prefix);
+        }
+    }
+    let symbol = prefix.map_or("", |prefix| prefix.symbol);
 
     format!("{rounded_number}{symbol}{unit}")
 }
 
-pub fn round_with_power(number: f64, unit: &str) -> String {
+pub fn round_with_power_default(number: f64, unit: &str) -> String {
+    round_with_power(number, unit, FormatOptions::default())
+}
+pub fn round_with_power(number: f64, unit: &str, options: FormatOptions) -> String {
+    if let Some(special) = non_finite(number) {
+        return special.to_owned();
+    }
     const MUL: char = '·';
     // const MUL: char = '×';
 
-    let exp = pick_prefix(number).map(|p| p.exp).unwrap_or(0);
+    let (prefix, rounded_number) = round_with_prefix(number, options);
+    let exp = prefix.map(|p| p.exp).unwrap_or(0);
 
     if exp == 0 {
-        let rounded_number = round_to_three_significant_digits(number);
         format!("{rounded_number}{unit}")
     } else {
         let pretty_exp = superscrip(&exp.to_string());
 
-        let scaled_number = number / 10_f64.powi(exp.into());
-        let rounded_number = round_to_three_significant_digits(scaled_number);
         if rounded_number == "1" {
             format!("10{pretty_exp}{unit}")
         } else {
@@ -132,16 +282,122 @@ pub fn round_with_power(number: f64, unit: &str) -> String {
     }
 }
 
+/// Like [`round_with_power`], but with any exponent that is a multiple of three, so it keeps
+/// working past the largest and smallest prefixes.
+pub fn round_engineering(number: f64, unit: &str) -> String {
+    const MUL: char = '·';
+
+    if let Some(special) = non_finite(number) {
+        return special.to_owned();
+    }
+    if number == 0. {
+        return format!("0{unit}");
+    }
+
+    let mut exp = (number.abs().log10() / 3.).floor() as i32 * 3;
+    // `log10` isn't exact near powers of ten, so correct it against the actual scale.
+    if number.abs() / power_of_ten(exp) >= 1000. {
+        exp += 3;
+    } else if number.abs() / power_of_ten(exp) < 1. {
+        exp -= 3;
+    }
+
+    let round =
+        |exp| round_to_significant_digits(number / power_of_ten(exp), FormatOptions::default());
+    let mut rounded_number = round(exp);
+    if reaches(&rounded_number, 1000.) {
+        exp += 3;
+        rounded_number = round(exp);
+    }
+    let pretty_exp = superscrip(&exp.to_string());
+    match (exp, rounded_number.as_str()) {
+        (0, _) => format!("{rounded_number}{unit}"),
+        (_, "1") => format!("10{pretty_exp}{unit}"),
+        _ => format!("{rounded_number}{MUL}10{pretty_exp}{unit}"),
+    }
+}
+
+/// Reads back numbers like `1.5k`, `3 μs` or `2.5 GW`, returning the unscaled value.
+///
+/// Whatever follows the prefix is taken as the unit and ignored. Letters right after the number
+/// are read as a prefix whenever they can be, so `5m` is five thousandths and the `P` in `3 Pa`
+/// is peta. Use [`parse_si_unit`] when the unit is known.
+pub fn parse_si(s: &str) -> Option<f64> {
+    let (number, rest) = split_number(s)?;
+    // The longest prefix wins, and without one the whole rest is the unit.
+    let exp = (1..=rest.len())
+        .rev()
+        .filter(|&i| rest.is_char_boundary(i))
+        .find_map(|i| prefix_exp(&rest[..i]))
+        .unwrap_or(0);
+    Some(scale(number, exp))
+}
+/// Reads back numbers like `3 μs` or `2.5 GW` in `unit`, returning the unscaled value.
+///
+/// The unit can be left out, but nothing else may follow the prefix. It is stripped before looking
+/// for a prefix, so with the unit `"m"` both `5m` and `5 m` are five meters, and `5mm` is 0.005.
+pub fn parse_si_unit(s: &str, unit: &str) -> Option<f64> {
+    let s = s.trim();
+    let (number, rest) = split_number(s.strip_suffix(unit).unwrap_or(s))?;
+    let exp = match rest {
+        "" => 0,
+        _ => prefix_exp(rest)?,
+    };
+    Some(scale(number, exp))
+}
+/// Splits off the longest number at the start of `s`, trimming what's left.
+fn split_number(s: &str) -> Option<(f64, &str)> {
+    let s = s.trim();
+    (1..=s.len())
+        .rev()
+        .filter(|&i| s.is_char_boundary(i))
+        .find_map(|i| Some((s[..i].trim_end().parse::<f64>().ok()?, s[i..].trim())))
+}
+fn prefix_exp(symbol: &str) -> Option<i8> {
+    /// The micro sign, which looks the same as the Greek letter in `SI_PREFIXES`.
+    const MICRO_SIGN: &str = "\u{b5}";
+
+    match symbol {
+        MICRO_SIGN => Some(-6),
+        _ => SI_PREFIXES
+            .iter()
+            .find(|prefix| prefix.symbol == symbol)
+            .map(|prefix| prefix.exp),
+    }
+}
+fn scale(number: f64, exp: i8) -> f64 {
+    // Dividing keeps small values exact, like `3μ` being the same as `3e-6`.
+    match exp {
+        0.. => number * 10_f64.powi(exp.into()),
+        _ => number / 10_f64.powi((-exp).into()),
+    }
+}
+
+/// What to show instead of numbers that can't be scaled.
+fn non_finite(number: f64) -> Option<&'static str> {
+    if number.is_nan() {
+        Some("NaN")
+    } else if number == f64::INFINITY {
+        Some("∞")
+    } else if number == f64::NEG_INFINITY {
+        Some("-∞")
+    } else {
+        None
+    }
+}
+
 fn pick_prefix(number: f64) -> Option<&'static SIPrefix> {
     // 0 would have -inf prefix
     if number == 0. {
         return None;
     }
 
-    let order_of_magnitude = dbg!(number.abs().log10());
+    // Compared against the scale of each prefix rather than `log10`, which isn't exact
+    // right at powers of ten.
+    let number = number.abs();
 
     // There is no 'zeroth' prefix
-    if (0_f64..3_f64).contains(&order_of_magnitude) {
+    if (1_f64..1000_f64).contains(&number) {
         return None;
     }
 
@@ -149,21 +405,135 @@ fn pick_prefix(number: f64) -> Option<&'static SIPrefix> {
         SI_PREFIXES
             .iter()
             .filter(|prefix| prefix.exp % 3 == 0) // Pick one thousand increments.
-            .filter(|prefix| f64::from(prefix.exp) <= order_of_magnitude)
-            .max_by_key(|prefix| prefix.exp)
+            .find(|prefix| 10_f64.powi(prefix.exp.into()) <= number)
             .unwrap_or(&SI_PREFIXES[NUMBER_OF_PREFIXES - 1]),
     )
 }
 
-fn round_to_three_significant_digits(number: f64) -> String {
-    format!("{number:.3}")
-        .trim_end_matches('0')
-        .trim_end_matches('.')
-        .to_owned()
+/// Scales `number` to its prefix and rounds it, moving up a prefix if the rounding reaches the next
+/// one, so `999.7` is `1k` rather than `1000`.
+fn round_with_prefix(number: f64, options: FormatOptions) -> (Option<&'static SIPrefix>, String) {
+    let round = |prefix: Option<&SIPrefix>| {
+        let exp = prefix.map_or(0, |prefix| prefix.exp);
+        round_to_significant_digits(number / 10_f64.powi(exp.into()), options)
+    };
+    let prefix = pick_prefix(number);
+    let rounded_number = round(prefix);
+    let exp = prefix.map_or(0, |prefix| prefix.exp) + 3;
+    if !reaches(&rounded_number, 1000.) || exp > SI_PREFIXES[0].exp {
+        return (prefix, rounded_number);
+    }
+    // There's no prefix for `exp == 0`, which is the right one to move up to from milli.
+    let next = SI_PREFIXES.iter().find(|prefix| prefix.exp == exp);
+    (next, round(next))
+}
+/// Whether `rounded` got as big as `limit`, where the next prefix takes over.
+fn reaches(rounded: &str, limit: f64) -> bool {
+    rounded
+        .parse::<f64>()
+        .is_ok_and(|rounded| rounded.abs() >= limit)
 }
+
+/// The largest prefix not bigger than `number`, if any.
+fn pick_binary_prefix(number: f64) -> Option<&'static BinaryPrefix> {
+    BINARY_PREFIXES
+        .iter()
+        .find(|prefix| 1024_f64.powi(prefix.exp.into()) <= number.abs())
+}
+
+/// Exactly the closest `f64` to `10^exp`, which `powi` isn't for large exponents.
+fn power_of_ten(exp: i32) -> f64 {
+    format!("1e{exp}").parse().unwrap()
+}
+
+/// Rounds away decimals past `digits` significant ones. Integer digits are always kept.
+fn round_to_significant_digits(number: f64, options: FormatOptions) -> String {
+    if number == 0. {
+        return "0".to_owned();
+    }
+    let magnitude = number.abs().log10().floor() as i32;
+    let decimals = (i32::from(options.digits.max(1)) - 1 - magnitude).max(0) as usize;
+
+    let rounded = round_to_decimals(number, decimals, options.rounding);
+    if rounded.contains('.') {
+        rounded
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_owned()
+    } else {
+        rounded
+    }
+}
+/// Like [`round_to_decimals`], but negative `decimals` round to tens, hundreds, and so on.
+fn round_to_place(number: f64, decimals: i32, mode: RoundingMode) -> String {
+    if decimals >= 0 {
+        return round_to_decimals(number, decimals as usize, mode);
+    }
+    let zeros = decimals.unsigned_abs() as usize;
+    let rounded = round_to_decimals(number / 10_f64.powi(-decimals), 0, mode);
+    if rounded.trim_start_matches('-') == "0" {
+        return rounded;
+    }
+    rounded + &"0".repeat(zeros)
+}
+/// Rounds the shortest decimal representation of `number`, so that `2.3` truncates to
+/// `2.3` rather than to the `2.29` its binary value is closer to.
+fn round_to_decimals(number: f64, decimals: usize, mode: RoundingMode) -> String {
+    let repr = number.abs().to_string();
+    let (integer, fraction) = repr.split_once('.').unwrap_or((&repr, ""));
+    let (kept, dropped) = fraction.split_at(decimals.min(fraction.len()));
+
+    let mut digits: Vec<u8> = integer
+        .bytes()
+        .chain(kept.bytes())
+        .map(|b| b - b'0')
+        .chain((kept.len()..decimals).map(|_| 0))
+        .collect();
+
+    let negative = number.is_sign_negative();
+    let inexact = dropped.bytes().any(|b| b != b'0');
+    let round_up = match mode {
+        RoundingMode::HalfUp => matches!(dropped.as_bytes().first(), Some(b'5'..=b'9')),
+        RoundingMode::HalfEven => match dropped.as_bytes().first() {
+            Some(b'6'..=b'9') => true,
+            Some(b'5') => {
+                dropped[1..].bytes().any(|b| b != b'0') || digits.last().is_some_and(|d| d % 2 == 1)
+            }
+            _ => false,
+        },
+        RoundingMode::Truncate => false,
+        RoundingMode::Ceil => inexact && !negative,
+        RoundingMode::Floor => inexact && negative,
+    };
+    if round_up {
+        let carried = digits.iter_mut().rev().all(|d| {
+            *d = (*d + 1) % 10;
+            *d == 0
+        });
+        if carried {
+            digits.insert(0, 1);
+        }
+    }
+
+    let (integer, fraction) = digits.split_at(digits.len() - decimals);
+    let mut out = String::new();
+    if negative && digits.iter().any(|&d| d != 0) {
+        out.push('-');
+    }
+    out.extend(integer.iter().map(|&d| char::from(b'0' + d)));
+    if !fraction.is_empty() {
+        out.push('.');
+        out.extend(fraction.iter().map(|&d| char::from(b'0' + d)));
+    }
+    out
+}
+/// Characters without a superscript form, like `.`, are passed through unchanged.
 fn superscrip(value: &str) -> String {
     let map: HashMap<_, _> = SUPERSCRIPTS.into_iter().collect();
-    value.chars().map(|c| map.get(&c).unwrap()).collect()
+    value
+        .chars()
+        .map(|c| map.get(&c).copied().unwrap_or(c))
+        .collect()
 }
 
 #[cfg(test)]
@@ -178,7 +548,11 @@ mod tests {
             (1.1e0, None),
             (1e2, None),
             (9.9e2, None),
+            (999.999, None),
             (1e3, Some(3)),
+            (1000.001, Some(3)),
+            (999_999.999, Some(3)),
+            (1e6, Some(6)),
             (11e2, Some(3)),
             (1e4, Some(3)),
             (1e7, Some(6)),
@@ -188,6 +562,8 @@ mod tests {
             (1e-2, Some(-3)),
             (9.9e-2, Some(-3)),
             (1e-3, Some(-3)),
+            (0.999_999, Some(-3)),
+            (0.000_999_999, Some(-6)),
             (11e-2, Some(-3)),
             (0.099e-2, Some(-6)),
             (1e-4, Some(-6)),
@@ -201,4 +577,224 @@ mod tests {
             assert_eq!(pick_prefix(-number).as_ref().map(|p| p.exp), exp,);
         }
     }
+
+    #[test]
+    fn test_round_with_binary_prefix() {
+        const TESTS: &[(f64, &str)] = &[
+            (0., "0B"),
+            (1023., "1023B"),
+            (1024., "1KiB"),
+            (1536., "1.5KiB"),
+            (-1536., "-1.5KiB"),
+            (1047552., "1023KiB"),
+            // Rounding up to the next prefix.
+            (1023.9, "1KiB"),
+            (1048575., "1MiB"),
+            (1048576., "1MiB"),
+            (1073741824., "1GiB"),
+        ];
+        for &(number, expected) in TESTS {
+            assert_eq!(round_with_binary_prefix(number, "B"), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_si() {
+        const TESTS: &[(&str, Option<f64>)] = &[
+            ("12", Some(12.)),
+            ("1.5k", Some(1500.)),
+            (" 3 μs ", Some(3e-6)),
+            (" 3 μ ", Some(3e-6)),
+            ("3\u{b5}s", Some(3e-6)),
+            ("2.5 GW", Some(2.5e9)),
+            ("-4da", Some(-40.)),
+            ("7 kg", Some(7000.)),
+            ("5m", Some(5e-3)),
+            ("1e3k", Some(1e6)),
+            ("2 W", Some(2.)),
+            // Ambiguous, see `parse_si_unit` below.
+            ("3 Pa", Some(3e15)),
+            ("", None),
+            ("k", None),
+            ("abc", None),
+        ];
+        for &(s, expected) in TESTS {
+            assert_eq!(parse_si(s), expected, "{s:?}");
+        }
+
+        const UNIT_TESTS: &[(&str, &str, Option<f64>)] = &[
+            (" 3 μs ", "s", Some(3e-6)),
+            ("3\u{b5}s", "s", Some(3e-6)),
+            ("2.5 GW", "W", Some(2.5e9)),
+            ("2.5 G", "W", Some(2.5e9)),
+            ("7 kg", "g", Some(7000.)),
+            ("3 Pa", "Pa", Some(3.)),
+            ("3 kPa", "Pa", Some(3000.)),
+            ("2 h", "h", Some(2.)),
+            ("5 min", "min", Some(5.)),
+            ("3 d", "d", Some(3.)),
+            ("5m", "m", Some(5.)),
+            ("5mm", "m", Some(5e-3)),
+            ("5 kg", "m", None),
+            ("m", "m", None),
+        ];
+        for &(s, unit, expected) in UNIT_TESTS {
+            assert_eq!(parse_si_unit(s, unit), expected, "{s:?} in {unit:?}");
+        }
+    }
+
+    #[test]
+    fn test_round_to_significant_digits() {
+        const TESTS: &[(f64, u8, &str)] = &[
+            (0., 3, "0"),
+            (1.23456, 1, "1"),
+            (1.23456, 3, "1.23"),
+            (1.23456, 4, "1.235"),
+            (-12.345, 3, "-12.3"),
+            (0.012345, 2, "0.012"),
+            (123456., 3, "123456"),
+            (999.9, 3, "1000"),
+            (1.5, 3, "1.5"),
+        ];
+        for &(number, digits, expected) in TESTS {
+            let options = FormatOptions::digits(digits);
+            assert_eq!(round_to_significant_digits(number, options), expected);
+        }
+        let options = FormatOptions::digits(2);
+        assert_eq!(round_with_scaled_unit(1234.5, "W", options), "1.2kW");
+        assert_eq!(round_with_scaled_unit_default(1234.5, "W"), "1.23kW");
+        let options = FormatOptions::digits(4);
+        assert_eq!(round_with_power(1234.6, "", options), "1.235·10³");
+    }
+
+    #[test]
+    fn test_rounding_across_prefixes() {
+        const TESTS: &[(f64, &str)] = &[
+            (999.4, "999W"),
+            (999.7, "1kW"),
+            (-999.7, "-1kW"),
+            (999_700., "1MW"),
+            (0.99996, "1W"),
+            (0.000_999_7, "1mW"),
+            (999.7e30, "1000QW"),
+        ];
+        for &(number, expected) in TESTS {
+            assert_eq!(round_with_scaled_unit_default(number, "W"), expected);
+        }
+        assert_eq!(round_with_power_default(999.7, "W"), "10³W");
+        assert_eq!(round_with_power_default(0.99996, "W"), "1W");
+        assert_eq!(round_engineering(999.7, "W"), "10³W");
+        assert_eq!(round_engineering(999_700., "W"), "10⁶W");
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        use RoundingMode::*;
+        const TESTS: &[(f64, RoundingMode, &str)] = &[
+            (1.2345, HalfEven, "1.234"),
+            (1.2355, HalfEven, "1.236"),
+            (1.2345, HalfUp, "1.235"),
+            (-1.2345, HalfUp, "-1.235"),
+            (2.3, Truncate, "2.3"),
+            (1.2349, Truncate, "1.234"),
+            (1.2341, Ceil, "1.235"),
+            (-1.2341, Ceil, "-1.234"),
+            (1.2349, Floor, "1.234"),
+            (-1.2341, Floor, "-1.235"),
+            (9.9996, HalfUp, "10"),
+        ];
+        for &(number, rounding, expected) in TESTS {
+            let options = FormatOptions::digits(4).rounding(rounding);
+            assert_eq!(
+                round_to_significant_digits(number, options),
+                expected,
+                "{number} {rounding:?}"
+            );
+        }
+        assert_eq!(round_to_decimals(-0.001, 2, Truncate), "0.00");
+        assert_eq!(round_to_decimals(-0.001, 2, Floor), "-0.01");
+    }
+
+    #[test]
+    fn test_non_finite() {
+        const TESTS: &[(f64, &str)] = &[
+            (f64::NAN, "NaN"),
+            (f64::INFINITY, "∞"),
+            (f64::NEG_INFINITY, "-∞"),
+        ];
+        for &(number, expected) in TESTS {
+            assert_eq!(round_with_scaled_unit_default(number, "W"), expected);
+            assert_eq!(round_with_power_default(number, "W"), expected);
+            assert_eq!(round_with_binary_prefix(number, "B"), expected);
+        }
+    }
+
+    #[test]
+    fn test_round_engineering() {
+        const TESTS: &[(f64, &str)] = &[
+            (0., "0m"),
+            (12., "12m"),
+            (1234., "1.23·10³m"),
+            (0.5, "500·10⁻³m"),
+            (1e33, "10³³m"),
+            (1.5e34, "15·10³³m"),
+            (-2e-33, "-2·10⁻³³m"),
+            (1e-33, "10⁻³³m"),
+            (f64::NAN, "NaN"),
+        ];
+        for &(number, expected) in TESTS {
+            assert_eq!(round_engineering(number, "m"), expected);
+        }
+    }
+
+    #[test]
+    fn test_superscrip() {
+        assert_eq!(superscrip("-12"), "⁻¹²");
+        assert_eq!(superscrip("3.5"), "³.⁵");
+    }
+
+    #[test]
+    fn test_format_with_uncertainty() {
+        let point = |size, standard_uncertainty| super::super::Datapoint {
+            name: String::new(),
+            size,
+            standard_uncertainty,
+            comment: None,
+            refs: vec![],
+        };
+        const TESTS: &[(f64, Option<f64>, &str)] = &[
+            (1230., Some(40.), "1.23 ± 0.04 kg"),
+            (1230., Some(15.), "1.230 ± 0.015 kg"),
+            (1234., Some(56.), "1.23 ± 0.06 kg"),
+            (5., Some(12.), "5 ± 12 g"),
+            (500., Some(34.), "500 ± 30 g"),
+            (345., Some(345.), "300 ± 300 g"),
+            (543., Some(123.), "540 ± 120 g"),
+            (20., Some(345.), "0 ± 300 g"),
+            (1230., None, "1.23kg"),
+            (1230., Some(0.), "1.23kg"),
+        ];
+        for &(size, uncertainty, expected) in TESTS {
+            assert_eq!(
+                format_with_uncertainty(&point(size, uncertainty), "g"),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_count() {
+        const TESTS: &[(u64, &str)] = &[
+            (0, "0"),
+            (999, "999"),
+            (1000, "1k"),
+            (1200, "1.2k"),
+            (999_999, "999k"),
+            (1_000_000, "1M"),
+            (3_400_000, "3.4M"),
+        ];
+        for &(n, expected) in TESTS {
+            assert_eq!(format_count(n), expected);
+        }
+    }
 }
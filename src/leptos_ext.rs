@@ -1,12 +1,20 @@
+use gloo_render::{request_animation_frame, AnimationFrame};
 use leptos::{
-    create_memo, create_render_effect, create_rw_signal, untrack, RwSignal, Signal, SignalSet,
-    SignalUpdate, SignalWith, SignalWithUntracked,
+    as_child_of_current_owner, batch, create_memo, create_render_effect, create_rw_signal, html,
+    on_cleanup, spawn_local, untrack, Disposer, IntoView, RwSignal, Signal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, SignalWithUntracked, TimeoutHandle,
+    View,
 };
 use std::{
-    cell::RefCell,
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
     fmt,
+    future::Future,
+    iter::Sum,
     ops::{Deref, DerefMut, Not},
     rc::Rc,
+    time::Duration,
 };
 
 #[derive(Debug, Clone)]
@@ -25,12 +33,6 @@ impl<T> SharedBox<T> {
     {
         *self.inner.borrow()
     }
-    fn get_cloned(&self) -> T
-    where
-        T: Clone,
-    {
-        self.inner.borrow().clone()
-    }
     fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
         f(&*self.inner.borrow())
     }
@@ -38,12 +40,14 @@ impl<T> SharedBox<T> {
     fn set(&self, to: T) {
         *self.inner.borrow_mut() = to;
     }
-    fn from_to(&self, from: &T, to: T)
+    fn update<O>(&self, f: impl FnOnce(&mut T) -> O) -> O {
+        f(&mut *self.inner.borrow_mut())
+    }
+    fn take(&self) -> T
     where
-        T: fmt::Debug + Clone + PartialEq,
+        T: Default,
     {
-        assert_eq!(from, &self.get_cloned());
-        self.set(to);
+        self.inner.take()
     }
 }
 
@@ -70,6 +74,105 @@ pub trait ReadSignalExt:
         let f = RefCell::new(f);
         create_memo(move |_| self_.with(|v| untrack(|| f.borrow_mut()(v)))).into()
     }
+    /// Like [ReadSignalExt::map_dedup], but with a custom equality.
+    ///
+    /// `eq` should be a proper equivalence relation, or changes might be skipped unexpectedly.
+    #[track_caller]
+    fn map_dedup_by<U>(
+        &self,
+        mut f: impl FnMut(&Self::Inner) -> U + 'static,
+        eq: impl Fn(&U, &U) -> bool + 'static,
+    ) -> Signal<U> {
+        let ret = create_rw_signal(self.with_untracked(&mut f));
+        self.for_each_after_first(move |value| {
+            let new = f(value);
+            if !ret.with_untracked(|old| eq(old, &new)) {
+                ret.set(new);
+            }
+        });
+        ret.into()
+    }
+    /// Like [ReadSignalExt::map], but tracking both `self` and `other`.
+    #[track_caller]
+    fn combine<B, U>(
+        &self,
+        other: &B,
+        f: impl FnMut(&Self::Inner, &B::Inner) -> U + 'static,
+    ) -> Signal<U>
+    where
+        B: ReadSignalExt,
+    {
+        let self_ = self.clone();
+        let other = other.clone();
+        let f = RefCell::new(f);
+        (move || self_.with(|a| other.with(|b| untrack(|| f.borrow_mut()(a, b))))).into()
+    }
+    /// Like [ReadSignalExt::combine], but with three signals.
+    #[track_caller]
+    fn combine3<B, C, U>(
+        &self,
+        b: &B,
+        c: &C,
+        f: impl FnMut(&Self::Inner, &B::Inner, &C::Inner) -> U + 'static,
+    ) -> Signal<U>
+    where
+        B: ReadSignalExt,
+        C: ReadSignalExt,
+    {
+        let self_ = self.clone();
+        let b = b.clone();
+        let c = c.clone();
+        let f = RefCell::new(f);
+        (move || self_.with(|va| b.with(|vb| c.with(|vc| untrack(|| f.borrow_mut()(va, vb, vc))))))
+            .into()
+    }
+    /// Pairs the values of `self` and `other`, cloning both on each change.
+    #[track_caller]
+    fn zip<B>(&self, other: &B) -> Signal<(Self::Inner, B::Inner)>
+    where
+        B: ReadSignalExt,
+        Self::Inner: Clone,
+        B::Inner: Clone,
+    {
+        self.combine(other, |a, b| (a.clone(), b.clone()))
+    }
+    /// Runs the returned future for each Inner, *including* the current one, and holds its output.
+    ///
+    /// Outputs of futures started before the latest change are discarded.
+    #[track_caller]
+    fn map_async<Fut, U>(&self, mut f: impl FnMut(&Self::Inner) -> Fut + 'static) -> Signal<Load<U>>
+    where
+        Fut: Future<Output = U> + 'static,
+    {
+        let ret = create_rw_signal(Load::Loading);
+        let generation = SharedBox::new(0_u64);
+        self.for_each(move |value| {
+            let current = generation.get() + 1;
+            generation.set(current);
+
+            ret.set(Load::Loading);
+            let future = f(value);
+            let generation = generation.clone();
+            spawn_local(async move {
+                let output = future.await;
+                if generation.get() == current {
+                    // The signal might have been disposed of while waiting.
+                    let _ = ret.try_set(Load::Ready(output));
+                }
+            });
+        });
+        ret.into()
+    }
+    /// Tracks both the outer signal and whichever inner signal it currently holds.
+    #[track_caller]
+    fn flatten<T>(&self) -> Signal<T>
+    where
+        Self::Inner: ReadSignalExt<Inner = T>,
+        T: Clone,
+    {
+        let self_ = self.clone();
+        (move || self_.with(|inner| inner.with(Clone::clone))).into()
+    }
     #[track_caller]
     fn map_window<U>(
         &self,
@@ -88,6 +191,22 @@ pub trait ReadSignalExt:
         });
         ret.read_only().into()
     }
+    /// The value before the latest change, or [None] if there has been no change yet.
+    #[track_caller]
+    fn previous(&self) -> Signal<Option<Self::Inner>>
+    where
+        Self::Inner: Clone,
+    {
+        self.map_window(|old, _| old.cloned())
+    }
+    /// The previous and current values, both starting as the current one.
+    #[track_caller]
+    fn pairwise(&self) -> Signal<(Self::Inner, Self::Inner)>
+    where
+        Self::Inner: Clone,
+    {
+        self.map_window(|old, new| (old.unwrap_or(new).clone(), new.clone()))
+    }
 
     #[track_caller]
     fn dedup(&self) -> Signal<Self::Inner>
@@ -118,6 +237,222 @@ pub trait ReadSignalExt:
         });
         ret.into()
     }
+    /// Holds the last [Some] returned by the provided closure, or [None] until there is one.
+    #[track_caller]
+    fn filter_map<U>(
+        &self,
+        mut f: impl FnMut(&Self::Inner) -> Option<U> + 'static,
+    ) -> Signal<Option<U>> {
+        let ret = create_rw_signal(None);
+        self.for_each(move |value| {
+            if let Some(value) = f(value) {
+                ret.set(Some(value));
+            }
+        });
+        ret.into()
+    }
+
+    /// Only emits the latest value once no changes have happened for `ms` milliseconds.
+    ///
+    /// The current value is passed through immediately, only later changes are delayed.
+    #[track_caller]
+    fn debounce(&self, ms: u32) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        let ret = create_rw_signal(self.with_untracked(Clone::clone));
+        let pending: SharedBox<Option<TimeoutHandle>> = SharedBox::new(None);
+
+        on_cleanup({
+            let pending = pending.clone();
+            move || clear_timeout(pending.take())
+        });
+
+        self.for_each_after_first(move |value| {
+            clear_timeout(pending.take());
+            let value = value.clone();
+            pending.set(set_timeout(ms, move || ret.set(value)));
+        });
+        ret.into()
+    }
+    /// Re-emits every change `ms` milliseconds later, in order and without dropping any.
+    ///
+    /// The current value is passed through immediately.
+    #[track_caller]
+    fn delay(&self, ms: u32) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        let ret = create_rw_signal(self.with_untracked(Clone::clone));
+        let pending: SharedBox<BTreeMap<u64, TimeoutHandle>> = SharedBox::new(BTreeMap::new());
+        let next = SharedBox::new(0_u64);
+
+        on_cleanup({
+            let pending = pending.clone();
+            move || {
+                for handle in pending.take().into_values() {
+                    handle.clear();
+                }
+            }
+        });
+
+        self.for_each_after_first(move |value| {
+            let id = next.get();
+            next.set(id + 1);
+
+            let value = value.clone();
+            let inner = pending.clone();
+            let handle = set_timeout(ms, move || {
+                inner.update(|pending| pending.remove(&id));
+                ret.set(value);
+            });
+            if let Some(handle) = handle {
+                pending.update(|pending| pending.insert(id, handle));
+            }
+        });
+        ret.into()
+    }
+    /// Emits at most once every `ms` milliseconds, keeping both the leading and trailing values.
+    #[track_caller]
+    fn throttle(&self, ms: u32) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        self.throttle_with(ms, ThrottleOptions::default())
+    }
+    /// Emits at most once every `ms` milliseconds, see [ThrottleOptions] for which values are kept.
+    ///
+    /// The current value is passed through immediately, and does not start an interval.
+    #[track_caller]
+    fn throttle_with(&self, ms: u32, options: ThrottleOptions) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        let throttle = Rc::new(Throttle {
+            ret: create_rw_signal(self.with_untracked(Clone::clone)),
+            ms,
+            options,
+            window: SharedBox::new(None),
+            trailing: SharedBox::new(None),
+        });
+        let ret = throttle.ret;
+
+        on_cleanup({
+            let throttle = throttle.clone();
+            move || clear_timeout(throttle.window.take())
+        });
+
+        self.for_each_after_first(move |value| throttle.push(value.clone()));
+        ret.into()
+    }
+    /// Emits at most once per animation frame, with the latest value received before it.
+    #[track_caller]
+    fn throttle_animation_frame(&self) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        let ret = create_rw_signal(self.with_untracked(Clone::clone));
+        let latest: SharedBox<Option<Self::Inner>> = SharedBox::new(None);
+        let frame: SharedBox<Option<AnimationFrame>> = SharedBox::new(None);
+
+        on_cleanup({
+            let frame = frame.clone();
+            move || drop(frame.take()) // Dropping cancels the request.
+        });
+
+        self.for_each_after_first(move |value| {
+            latest.set(Some(value.clone()));
+            if frame.with(Option::is_some) {
+                return;
+            }
+
+            let latest = latest.clone();
+            let inner = frame.clone();
+            frame.set(Some(request_animation_frame(move |_| {
+                drop(inner.take());
+                if let Some(value) = latest.take() {
+                    ret.set(value);
+                }
+            })));
+        });
+        ret.into()
+    }
+
+    /// Holds the value of `self` as of the latest change of `trigger`, ignoring other changes.
+    #[track_caller]
+    fn sample(&self, trigger: &impl ReadSignalExt) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        let ret = create_rw_signal(self.with_untracked(Clone::clone));
+        let self_ = self.clone();
+        trigger.for_each_after_first(move |_| ret.set(self_.with_untracked(Clone::clone)));
+        ret.into()
+    }
+
+    #[track_caller]
+    fn map_ok<T, E, U>(&self, mut f: impl FnMut(&T) -> U + 'static) -> Signal<Result<U, E>>
+    where
+        Self: ReadSignalExt<Inner = Result<T, E>>,
+        E: Clone,
+    {
+        self.map(move |r| r.as_ref().map(&mut f).map_err(E::clone))
+    }
+    #[track_caller]
+    fn map_err<T, E, F>(&self, mut f: impl FnMut(&E) -> F + 'static) -> Signal<Result<T, F>>
+    where
+        Self: ReadSignalExt<Inner = Result<T, E>>,
+        T: Clone,
+    {
+        self.map(move |r| r.as_ref().map(T::clone).map_err(&mut f))
+    }
+    /// Splits the result into two deduplicated halves, so each only fires when its own side changes.
+    #[track_caller]
+    fn split<T, E>(&self) -> (Signal<Option<T>>, Signal<Option<E>>)
+    where
+        Self: ReadSignalExt<Inner = Result<T, E>>,
+        T: Clone + PartialEq,
+        E: Clone + PartialEq,
+    {
+        (
+            self.map_dedup(|r| r.as_ref().ok().cloned()),
+            self.map_dedup(|r| r.as_ref().err().cloned()),
+        )
+    }
+
+    #[track_caller]
+    fn map_some<T, U>(&self, mut f: impl FnMut(&T) -> U + 'static) -> Signal<Option<U>>
+    where
+        Self: ReadSignalExt<Inner = Option<T>>,
+    {
+        self.map(move |o| o.as_ref().map(&mut f))
+    }
+    /// The inner value, or `default` while there is none.
+    #[track_caller]
+    fn unwrap_or<T>(&self, default: T) -> Signal<T>
+    where
+        Self: ReadSignalExt<Inner = Option<T>>,
+        T: Clone,
+    {
+        self.map(move |o| o.as_ref().unwrap_or(&default).clone())
+    }
+    /// The inner value, or the output of `f` while there is none.
+    #[track_caller]
+    fn unwrap_or_else<T>(&self, mut f: impl FnMut() -> T + 'static) -> Signal<T>
+    where
+        Self: ReadSignalExt<Inner = Option<T>>,
+        T: Clone,
+    {
+        self.map(move |o| o.clone().unwrap_or_else(&mut f))
+    }
+    /// Maps each element along with its index, keeping their order.
+    #[track_caller]
+    fn map_indexed<T, U>(&self, mut f: impl FnMut(usize, &T) -> U + 'static) -> Signal<Vec<U>>
+    where
+        Self: ReadSignalExt<Inner = Vec<T>>,
+    {
+        self.map(move |v| v.iter().enumerate().map(|(i, t)| f(i, t)).collect())
+    }
 
     #[track_caller]
     fn not(&self) -> Signal<<Self::Inner as Not>::Output>
@@ -155,6 +490,37 @@ pub trait ReadSignalExt:
             }
         });
     }
+    /// Like [ReadSignalExt::for_each], but passes each Inner through unchanged.
+    ///
+    /// Useful to log values in the middle of a chain of combinators.
+    #[track_caller]
+    fn inspect(&self, mut f: impl FnMut(&Self::Inner) + 'static) -> Signal<Self::Inner>
+    where
+        Self::Inner: Clone,
+    {
+        let ret = create_rw_signal(self.with_untracked(Clone::clone));
+        self.for_each(move |value| {
+            f(value);
+            ret.set(value.clone());
+        });
+        ret.into()
+    }
+    /// Folds each Inner of the signal, *including* the current one, into the accumulator.
+    ///
+    /// The accumulator is kept for as long as the returned signal lives.
+    #[track_caller]
+    fn scan<A>(&self, init: A, mut f: impl FnMut(&mut A, &Self::Inner) + 'static) -> Signal<A> {
+        let ret = create_rw_signal(init);
+        self.for_each(move |value| ret.update(|acc| f(acc, value)));
+        ret.into()
+    }
+    /// Counts the changes of the signal, starting at 0 for the current value.
+    #[track_caller]
+    fn count_changes(&self) -> Signal<usize> {
+        let ret = create_rw_signal(0);
+        self.for_each_after_first(move |_| ret.update(|count| *count += 1));
+        ret.into()
+    }
     /// Runs a function when the signal changes, taking the old and new Inner as arguments
     #[track_caller]
     fn for_each_window(&self, mut f: impl FnMut(&Self::Inner, &Self::Inner) + 'static)
@@ -221,22 +587,81 @@ pub trait WriteSignalExt:
             signal: self.clone(),
         }
     }
+    /// Like [WriteSignalExt::modify], but the signal is only set if the value was actually changed.
+    #[track_caller]
+    fn modify_if_changed(&self) -> ModifyIfChanged<Self>
+    where
+        Self::Inner: PartialEq + Clone,
+    {
+        let original = self.with_untracked(Clone::clone);
+        ModifyIfChanged {
+            value: Some(original.clone()),
+            original,
+            signal: self.clone(),
+        }
+    }
+
+    /// Sets the signal to `true`, and back to `false` after `ms` milliseconds.
+    ///
+    /// Pulsing again before then restarts the countdown.
+    #[track_caller]
+    fn pulse(&self, ms: u32)
+    where
+        Self: ReadSignalExt<Inner = bool> + PartialEq,
+    {
+        let id = start_pulse(self);
+        let signal = self.clone();
+        let timeout = set_timeout(ms, move || {
+            if end_pulse(id).is_some() {
+                signal.set(false);
+            }
+        });
+        PULSES.with(|pulses| {
+            if let Some(pulse) = pulses.borrow_mut().iter_mut().find(|p| p.id == id) {
+                pulse.timeout = timeout;
+            }
+        });
+        on_cleanup(move || {
+            if let Some(pulse) = end_pulse(id) {
+                clear_timeout(pulse.timeout);
+            }
+        });
+    }
+
+    /// A read/write projection: reads map the current value, and writes update it in place.
+    ///
+    /// Unlike [WriteSignalExt::double_bind], no independent signal is created.
+    #[track_caller]
+    fn slice<U>(
+        &self,
+        get: impl Fn(&Self::Inner) -> U + 'static,
+        set: impl Fn(&mut Self::Inner, U) + 'static,
+    ) -> Slice<U> {
+        let self_ = self.clone();
+        Slice {
+            read: self.map(get),
+            write: Rc::new(move |value| self_.update(|inner| set(inner, value))),
+        }
+    }
 
     // TODO: get rid of this by adding derived rw signals? Slices?
     // Here it would be useful to have the rw equivalent of [Signal].
+    /// Writes arriving on one side while that side is still being propagated
+    /// (for example from within `from`/`to`) are replayed once the propagation is done.
     fn double_bind<U>(
         self,
         mut from: impl FnMut(&Self::Inner) -> U + 'static,
-        mut to: impl FnMut(&U) -> Self::Inner + 'static,
+        to: impl FnMut(&U) -> Self::Inner + 'static,
     ) -> RwSignal<U>
     where
         U: Clone,
     {
+        /// `again` is set when the side being propagated is written to in the meantime.
         #[derive(Clone, Copy, PartialEq, Eq, Debug)]
         enum Status {
             Idle,
-            ReactingParent,
-            ReactingChild,
+            ReactingParent { again: bool },
+            ReactingChild { again: bool },
         }
 
         let child: RwSignal<U> = create_rw_signal(self.with_untracked(&mut from));
@@ -245,26 +670,46 @@ pub trait WriteSignalExt:
 
         self.for_each_after_first({
             let lock = lock.clone();
+            let self_ = self.clone();
             move |value| match lock.get() {
                 Status::Idle => {
-                    lock.from_to(&Status::Idle, Status::ReactingParent);
+                    lock.set(Status::ReactingParent { again: false });
                     child.set(from(value));
-                    lock.from_to(&Status::ReactingParent, Status::Idle);
+                    while lock.get() == (Status::ReactingParent { again: true }) {
+                        lock.set(Status::ReactingParent { again: false });
+                        child.set(self_.with_untracked(&mut from));
+                    }
+                    lock.set(Status::Idle);
                 }
-                Status::ReactingParent => unreachable!(),
-                Status::ReactingChild => {}
+                Status::ReactingParent { .. } => lock.set(Status::ReactingParent { again: true }),
+                Status::ReactingChild { .. } => {}
             }
         });
 
         let self_ = self.clone();
-        child.for_each_after_first(move |value| match lock.get() {
-            Status::Idle => {
-                lock.from_to(&Status::Idle, Status::ReactingChild);
-                self_.set(to(value));
-                lock.from_to(&Status::ReactingChild, Status::Idle);
+        let to = RefCell::new(to);
+        let first = Cell::new(true);
+        create_render_effect(move |_| {
+            // Cloned out so that `to` is free to write back to the child.
+            let value = child.get();
+            if first.replace(false) {
+                return;
             }
-            Status::ReactingParent => {}
-            Status::ReactingChild => unreachable!(),
+            untrack(|| match lock.get() {
+                Status::Idle => {
+                    lock.set(Status::ReactingChild { again: false });
+                    let new = to.borrow_mut()(&value);
+                    self_.set(new);
+                    while lock.get() == (Status::ReactingChild { again: true }) {
+                        lock.set(Status::ReactingChild { again: false });
+                        let new = to.borrow_mut()(&child.get_untracked());
+                        self_.set(new);
+                    }
+                    lock.set(Status::Idle);
+                }
+                Status::ReactingParent { .. } => {}
+                Status::ReactingChild { .. } => lock.set(Status::ReactingChild { again: true }),
+            })
         });
 
         child
@@ -278,6 +723,20 @@ impl<T, Value> WriteSignalExt for T where
 {
 }
 
+/// Tracks all the provided signals, keeping their order.
+///
+/// See [SignalBag] for when the set of signals is not known upfront.
+#[track_caller]
+pub fn collect_signals<T: Clone + 'static>(signals: Vec<Signal<T>>) -> Signal<Vec<T>> {
+    (move || signals.iter().map(SignalGet::get).collect()).into()
+}
+/// Keeps two signals in sync, starting from the current value of `a`.
+pub fn link<T: Clone + PartialEq + 'static>(a: RwSignal<T>, b: RwSignal<T>) {
+    b.set_if_changed(a.get_untracked());
+    a.for_each_after_first(move |value| b.set_if_changed(value.clone()));
+    b.for_each_after_first(move |value| a.set_if_changed(value.clone()));
+}
+
 pub struct Modify<T: WriteSignalExt> {
     value: Option<<T as ReadSignalExt>::Inner>,
     signal: T,
@@ -312,47 +771,425 @@ impl<T: WriteSignalExt> Drop for Modify<T> {
     }
 }
 
-/// Useful to handle an aggregation over a variable (increasing for now) number of signals.
-#[derive(Default)]
-pub struct SignalBag<I> {
-    trigger: RwSignal<()>,
-    bag: Rc<RefCell<Vec<Getter<I>>>>,
+pub struct ModifyIfChanged<T: WriteSignalExt>
+where
+    <T as ReadSignalExt>::Inner: PartialEq,
+{
+    original: <T as ReadSignalExt>::Inner,
+    value: Option<<T as ReadSignalExt>::Inner>,
+    signal: T,
 }
-type Getter<I> = Box<dyn Fn() -> I + 'static>;
-impl<I: Clone + 'static> SignalBag<I> {
-    pub fn new() -> Self {
-        Self {
-            trigger: create_rw_signal(()),
-            bag: Rc::default(),
-        }
+impl<T> fmt::Debug for ModifyIfChanged<T>
+where
+    T: WriteSignalExt + fmt::Debug,
+    <T as ReadSignalExt>::Inner: PartialEq + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ModifyIfChanged")
+            .field("original", &self.original)
+            .field("value", &self.value)
+            .field("signal", &self.signal)
+            .finish()
     }
-    pub fn push(&self, signal: impl ReadSignalExt<Inner = I> + 'static) {
-        // We make sure future changes trigger an update.
-        let trigger = self.trigger;
-        signal.for_each_after_first(move |_| trigger.trigger_subscribers());
+}
+impl<T: WriteSignalExt> Deref for ModifyIfChanged<T>
+where
+    <T as ReadSignalExt>::Inner: PartialEq,
+{
+    type Target = <T as ReadSignalExt>::Inner;
 
-        self.bag
-            .borrow_mut()
-            .push(Box::new(move || signal.with(Clone::clone)));
-        self.trigger.trigger_subscribers();
+    fn deref(&self) -> &Self::Target {
+        self.value.as_ref().unwrap()
     }
-    pub fn map<O: 'static>(&self, mut f: impl FnMut(Vec<I>) -> O + 'static) -> Signal<O> {
-        let bag = self.bag.clone();
-        self.trigger.map(move |&()| {
-            let inputs: Vec<_> = bag.borrow().iter().map(|f| f()).collect();
-            f(inputs)
-        })
+}
+impl<T: WriteSignalExt> DerefMut for ModifyIfChanged<T>
+where
+    <T as ReadSignalExt>::Inner: PartialEq,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value.as_mut().unwrap()
     }
 }
-impl<I: fmt::Debug> fmt::Debug for SignalBag<I> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<T: WriteSignalExt> Drop for ModifyIfChanged<T>
+where
+    <T as ReadSignalExt>::Inner: PartialEq,
+{
+    fn drop(&mut self) {
+        let value = self.value.take().unwrap();
+        if value != self.original {
+            self.signal.set(value);
+        }
+    }
+}
+
+/// See [WriteSignalExt::slice].
+pub struct Slice<T: 'static> {
+    read: Signal<T>,
+    write: Rc<dyn Fn(T)>,
+}
+impl<T: 'static> Clone for Slice<T> {
+    fn clone(&self) -> Self {
+        Self {
+            read: self.read,
+            write: self.write.clone(),
+        }
+    }
+}
+impl<T: 'static> fmt::Debug for Slice<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Slice").field("read", &self.read).finish()
+    }
+}
+impl<T: 'static> SignalWithUntracked for Slice<T> {
+    type Value = T;
+
+    fn with_untracked<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> O {
+        self.read.with_untracked(f)
+    }
+
+    fn try_with_untracked<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> Option<O> {
+        self.read.try_with_untracked(f)
+    }
+}
+impl<T: 'static> SignalWith for Slice<T> {
+    type Value = T;
+
+    fn with<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> O {
+        self.read.with(f)
+    }
+
+    fn try_with<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> Option<O> {
+        self.read.try_with(f)
+    }
+}
+impl<T: Clone + 'static> SignalGetUntracked for Slice<T> {
+    type Value = T;
+
+    fn get_untracked(&self) -> Self::Value {
+        self.read.get_untracked()
+    }
+
+    fn try_get_untracked(&self) -> Option<Self::Value> {
+        self.read.try_get_untracked()
+    }
+}
+impl<T: Clone + 'static> SignalGet for Slice<T> {
+    type Value = T;
+
+    fn get(&self) -> Self::Value {
+        self.read.get()
+    }
+
+    fn try_get(&self) -> Option<Self::Value> {
+        self.read.try_get()
+    }
+}
+impl<T: 'static> SignalSet for Slice<T> {
+    type Value = T;
+
+    fn set(&self, new_value: T) {
+        (self.write)(new_value);
+    }
+
+    fn try_set(&self, new_value: T) -> Option<T> {
+        (self.write)(new_value);
+        None
+    }
+}
+impl<T: Clone + 'static> SignalUpdate for Slice<T> {
+    type Value = T;
+
+    fn update(&self, f: impl FnOnce(&mut Self::Value)) {
+        let mut value = self.read.get_untracked();
+        f(&mut value);
+        (self.write)(value);
+    }
+
+    fn try_update<O>(&self, f: impl FnOnce(&mut Self::Value) -> O) -> Option<O> {
+        let mut value = self.read.try_get_untracked()?;
+        let ret = f(&mut value);
+        (self.write)(value);
+        Some(ret)
+    }
+}
+
+/// Which values [ReadSignalExt::throttle_with] lets through.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ThrottleOptions {
+    /// Emit the first value of a burst immediately.
+    pub leading: bool,
+    /// Emit the last suppressed value of a burst once the interval is over.
+    pub trailing: bool,
+}
+impl Default for ThrottleOptions {
+    fn default() -> Self {
+        Self {
+            leading: true,
+            trailing: true,
+        }
+    }
+}
+struct Throttle<T: 'static> {
+    ret: RwSignal<T>,
+    ms: u32,
+    options: ThrottleOptions,
+    /// Set while an interval is running.
+    window: SharedBox<Option<TimeoutHandle>>,
+    trailing: SharedBox<Option<T>>,
+}
+impl<T: 'static> Throttle<T> {
+    fn push(self: &Rc<Self>, value: T) {
+        if self.window.with(Option::is_some) {
+            self.trailing.set(Some(value));
+            return;
+        }
+
+        if self.options.leading {
+            self.ret.set(value);
+        } else {
+            self.trailing.set(Some(value));
+        }
+        self.open_window();
+    }
+    fn open_window(self: &Rc<Self>) {
+        let self_ = self.clone();
+        self.window
+            .set(set_timeout(self.ms, move || self_.close_window()));
+    }
+    fn close_window(self: &Rc<Self>) {
+        self.window.set(None);
+        match self.trailing.take() {
+            Some(value) if self.options.trailing => {
+                self.ret.set(value);
+                self.open_window();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn set_timeout(ms: u32, f: impl FnOnce() + 'static) -> Option<TimeoutHandle> {
+    leptos::set_timeout_with_handle(f, Duration::from_millis(ms.into()))
+        .map_err(|e| log::error!("Failed to set timeout: {e:?}"))
+        .ok()
+}
+fn clear_timeout(handle: Option<TimeoutHandle>) {
+    if let Some(handle) = handle {
+        handle.clear();
+    }
+}
+
+thread_local! {
+    /// The latest [WriteSignalExt::pulse] of each signal, until it's reset.
+    static PULSES: RefCell<Vec<Pulse>> = RefCell::default();
+    static NEXT_PULSE: Cell<u64> = const { Cell::new(0) };
+}
+struct Pulse {
+    signal: Box<dyn Any>,
+    id: u64,
+    timeout: Option<TimeoutHandle>,
+}
+/// Sets the signal to `true`, cancelling the reset of any earlier pulse.
+fn start_pulse<S>(signal: &S) -> u64
+where
+    S: WriteSignalExt<Inner = bool> + PartialEq,
+{
+    let earlier = PULSES.with(|pulses| {
+        let mut pulses = pulses.borrow_mut();
+        let i = pulses
+            .iter()
+            .position(|p| p.signal.downcast_ref::<S>() == Some(signal))?;
+        Some(pulses.swap_remove(i))
+    });
+    if let Some(earlier) = earlier {
+        clear_timeout(earlier.timeout);
+    }
+
+    let id = NEXT_PULSE.with(|next| next.replace(next.get() + 1));
+    PULSES.with(|pulses| {
+        pulses.borrow_mut().push(Pulse {
+            signal: Box::new(signal.clone()),
+            id,
+            timeout: None,
+        })
+    });
+    signal.set(true);
+    id
+}
+/// Returns [None] if the pulse was already reset or replaced by a later one.
+fn end_pulse(id: u64) -> Option<Pulse> {
+    PULSES.with(|pulses| {
+        let mut pulses = pulses.borrow_mut();
+        let i = pulses.iter().position(|p| p.id == id)?;
+        Some(pulses.swap_remove(i))
+    })
+}
+
+/// Useful to handle an aggregation over a variable number of signals.
+///
+/// Signals can optionally be associated with a key, see [SignalBag::push_keyed].
+#[derive(Default)]
+pub struct SignalBag<I, K = ()> {
+    trigger: RwSignal<()>,
+    bag: Rc<RefCell<Bag<I, K>>>,
+}
+struct Bag<I, K> {
+    next: u64,
+    /// The disposer drops the subscription to the signal, and with it the last reference to it.
+    getters: BTreeMap<u64, (K, Getter<I>, Disposer)>,
+}
+type Getter<I> = Rc<dyn Fn() -> I + 'static>;
+/// Identifies a signal pushed into a [SignalBag], see [SignalBag::remove].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct BagHandle(u64);
+impl<I: Clone + 'static> SignalBag<I> {
+    pub fn push(&self, signal: impl ReadSignalExt<Inner = I> + 'static) -> BagHandle {
+        self.push_keyed((), signal)
+    }
+}
+impl<I: Clone + 'static, K: 'static> SignalBag<I, K> {
+    pub fn new() -> Self {
+        Self {
+            trigger: create_rw_signal(()),
+            bag: Rc::default(),
+        }
+    }
+    pub fn push_keyed(&self, key: K, signal: impl ReadSignalExt<Inner = I> + 'static) -> BagHandle {
+        let handle = {
+            let mut bag = self.bag.borrow_mut();
+            let handle = BagHandle(bag.next);
+            bag.next += 1;
+            handle
+        };
+
+        // We make sure future changes trigger an update, for as long as the signal is in the bag.
+        let trigger = self.trigger;
+        let subscribed = signal.clone();
+        let ((), disposer) = as_child_of_current_owner(move |()| {
+            subscribed.for_each_after_first(move |_| trigger.trigger_subscribers())
+        })(());
+
+        let getter: Getter<I> = Rc::new(move || signal.with(Clone::clone));
+        self.bag
+            .borrow_mut()
+            .getters
+            .insert(handle.0, (key, getter, disposer));
+        self.trigger.trigger_subscribers();
+
+        handle
+    }
+    /// Removes a signal from the bag, does nothing if it was already removed.
+    pub fn remove(&self, handle: BagHandle) {
+        // Dropped outside of the borrow, since disposing of the subscription may run other code.
+        let removed = self.bag.borrow_mut().getters.remove(&handle.0);
+        if removed.is_some() {
+            self.trigger.trigger_subscribers();
+        }
+    }
+    /// Removes all signals pushed with the provided key.
+    pub fn remove_key(&self, key: &K)
+    where
+        K: PartialEq,
+    {
+        let removed: BTreeMap<_, _> = {
+            let mut bag = self.bag.borrow_mut();
+            let (removed, kept) = std::mem::take(&mut bag.getters)
+                .into_iter()
+                .partition(|(_, (k, _, _))| k == key);
+            bag.getters = kept;
+            removed
+        };
+        if !removed.is_empty() {
+            self.trigger.trigger_subscribers();
+        }
+    }
+    /// Removes all signals from the bag.
+    pub fn clear(&self) {
+        let removed = std::mem::take(&mut self.bag.borrow_mut().getters);
+        if !removed.is_empty() {
+            self.trigger.trigger_subscribers();
+        }
+    }
+
+    /// The number of signals currently in the bag. This is *not* reactive.
+    pub fn len(&self) -> usize {
+        self.bag.borrow().getters.len()
+    }
+    /// Whether the bag currently holds no signals. This is *not* reactive.
+    pub fn is_empty(&self) -> bool {
+        self.bag.borrow().getters.is_empty()
+    }
+
+    pub fn map<O: 'static>(&self, mut f: impl FnMut(Vec<I>) -> O + 'static) -> Signal<O> {
+        let bag = self.bag.clone();
+        self.trigger.map(move |&()| {
+            // The getters are cloned out, so the bag can be edited while they run.
+            let getters = bag.borrow().getters();
+            let inputs: Vec<_> = getters.iter().map(|f| f()).collect();
+            f(inputs)
+        })
+    }
+    /// Like [SignalBag::map], but only notifies subscribers when the output changes.
+    pub fn map_memo<O: PartialEq + 'static>(
+        &self,
+        f: impl FnMut(Vec<I>) -> O + 'static,
+    ) -> Signal<O> {
+        let bag = self.bag.clone();
+        let trigger = self.trigger;
+        let f = RefCell::new(f);
+        create_memo(move |_| {
+            trigger.track();
+            untrack(|| {
+                let getters = bag.borrow().getters();
+                let inputs: Vec<_> = getters.iter().map(|g| g()).collect();
+                f.borrow_mut()(inputs)
+            })
+        })
+        .into()
+    }
+    /// Folds the current values of all signals in the bag, without collecting them first.
+    pub fn reduce<O: Clone + 'static>(
+        &self,
+        init: O,
+        mut f: impl FnMut(O, I) -> O + 'static,
+    ) -> Signal<O> {
+        let bag = self.bag.clone();
+        self.trigger.map(move |&()| {
+            let getters = bag.borrow().getters();
+            getters.iter().fold(init.clone(), |acc, g| f(acc, g()))
+        })
+    }
+    pub fn sum(&self) -> Signal<I>
+    where
+        I: Sum,
+    {
+        let bag = self.bag.clone();
+        self.trigger.map(move |&()| {
+            let getters = bag.borrow().getters();
+            getters.iter().map(|g| g()).sum()
+        })
+    }
+    pub fn map_keyed<O: 'static>(&self, mut f: impl FnMut(Vec<(K, I)>) -> O + 'static) -> Signal<O>
+    where
+        K: Clone,
+    {
+        let bag = self.bag.clone();
+        self.trigger.map(move |&()| {
+            let getters = bag.borrow().keyed_getters();
+            let inputs: Vec<_> = getters.into_iter().map(|(k, f)| (k, f())).collect();
+            f(inputs)
+        })
+    }
+}
+impl<I: fmt::Debug, K> fmt::Debug for SignalBag<I, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("SignalBag")
             .field("trigger", &self.trigger)
-            .field("bag_size", &self.bag.borrow().len())
+            .field("bag_size", &self.bag.borrow().getters.len())
             .finish()
     }
 }
-impl<I> Clone for SignalBag<I> {
+impl<I, K> Clone for SignalBag<I, K> {
     fn clone(&self) -> Self {
         Self {
             trigger: self.trigger,
@@ -360,12 +1197,255 @@ impl<I> Clone for SignalBag<I> {
         }
     }
 }
+impl<I, K> Bag<I, K> {
+    fn getters(&self) -> Vec<Getter<I>> {
+        self.getters.values().map(|(_, f, _)| f.clone()).collect()
+    }
+    fn keyed_getters(&self) -> Vec<(K, Getter<I>)>
+    where
+        K: Clone,
+    {
+        self.getters
+            .values()
+            .map(|(k, f, _)| (k.clone(), f.clone()))
+            .collect()
+    }
+}
+impl<I, K> Default for Bag<I, K> {
+    fn default() -> Self {
+        Self {
+            next: 0,
+            getters: BTreeMap::new(),
+        }
+    }
+}
+
+/// A signal keeping track of its previous values, so that changes can be undone and redone.
+///
+/// Writes through [SignalSet]/[SignalUpdate] are recorded, unless they leave the value unchanged.
+#[derive(Debug)]
+pub struct History<T: 'static> {
+    value: RwSignal<T>,
+    undo: RwSignal<Vec<T>>,
+    redo: RwSignal<Vec<T>>,
+    /// The maximum number of undo steps kept around.
+    max_depth: usize,
+}
+impl<T: Clone + PartialEq + 'static> History<T> {
+    pub fn new(value: T, max_depth: usize) -> Self {
+        Self {
+            value: create_rw_signal(value),
+            undo: create_rw_signal(vec![]),
+            redo: create_rw_signal(vec![]),
+            max_depth,
+        }
+    }
+
+    pub fn undo(&self) {
+        // Popping an empty stack would still notify subscribers.
+        if self.undo.with_untracked(Vec::is_empty) {
+            return;
+        }
+        let Some(previous) = self.undo.try_update(Vec::pop).flatten() else {
+            return;
+        };
+        batch(|| {
+            let current = self.value.get_untracked();
+            self.redo.update(|redo| redo.push(current));
+            self.value.set(previous);
+        });
+    }
+    pub fn redo(&self) {
+        if self.redo.with_untracked(Vec::is_empty) {
+            return;
+        }
+        let Some(next) = self.redo.try_update(Vec::pop).flatten() else {
+            return;
+        };
+        batch(|| {
+            let current = self.value.get_untracked();
+            self.undo
+                .update(|undo| push_bounded(undo, current, self.max_depth));
+            self.value.set(next);
+        });
+    }
+    pub fn can_undo(&self) -> Signal<bool> {
+        self.undo.map(|undo| !undo.is_empty())
+    }
+    pub fn can_redo(&self) -> Signal<bool> {
+        self.redo.map(|redo| !redo.is_empty())
+    }
+
+    fn record(&self, new: T) {
+        if self.value.with_untracked(|current| current == &new) {
+            return;
+        }
+        batch(|| {
+            let current = self.value.get_untracked();
+            self.undo
+                .update(|undo| push_bounded(undo, current, self.max_depth));
+            if !self.redo.with_untracked(Vec::is_empty) {
+                self.redo.update(Vec::clear);
+            }
+            self.value.set(new);
+        });
+    }
+}
+fn push_bounded<T>(stack: &mut Vec<T>, value: T, max: usize) {
+    stack.push(value);
+    if stack.len() > max {
+        stack.drain(..stack.len() - max);
+    }
+}
+impl<T: 'static> Copy for History<T> {}
+impl<T: 'static> Clone for History<T> {
+    #[allow(clippy::non_canonical_clone_impl)] // We don't need the T: Clone bound.
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value,
+            undo: self.undo,
+            redo: self.redo,
+            max_depth: self.max_depth,
+        }
+    }
+}
+impl<T: 'static> SignalWithUntracked for History<T> {
+    type Value = T;
+
+    fn with_untracked<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> O {
+        self.value.with_untracked(f)
+    }
+
+    fn try_with_untracked<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> Option<O> {
+        self.value.try_with_untracked(f)
+    }
+}
+impl<T: 'static> SignalWith for History<T> {
+    type Value = T;
+
+    fn with<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> O {
+        self.value.with(f)
+    }
+
+    fn try_with<O>(&self, f: impl FnOnce(&Self::Value) -> O) -> Option<O> {
+        self.value.try_with(f)
+    }
+}
+impl<T: Clone + 'static> SignalGetUntracked for History<T> {
+    type Value = T;
+
+    fn get_untracked(&self) -> Self::Value {
+        self.value.get_untracked()
+    }
+
+    fn try_get_untracked(&self) -> Option<Self::Value> {
+        self.value.try_get_untracked()
+    }
+}
+impl<T: Clone + 'static> SignalGet for History<T> {
+    type Value = T;
+
+    fn get(&self) -> Self::Value {
+        self.value.get()
+    }
+
+    fn try_get(&self) -> Option<Self::Value> {
+        self.value.try_get()
+    }
+}
+impl<T: Clone + PartialEq + 'static> SignalSet for History<T> {
+    type Value = T;
+
+    fn set(&self, new_value: T) {
+        self.record(new_value);
+    }
+
+    fn try_set(&self, new_value: T) -> Option<T> {
+        self.record(new_value);
+        None
+    }
+}
+impl<T: Clone + PartialEq + 'static> SignalUpdate for History<T> {
+    type Value = T;
+
+    fn update(&self, f: impl FnOnce(&mut Self::Value)) {
+        let mut value = self.value.get_untracked();
+        f(&mut value);
+        self.record(value);
+    }
+
+    fn try_update<O>(&self, f: impl FnOnce(&mut Self::Value) -> O) -> Option<O> {
+        let mut value = self.value.try_get_untracked()?;
+        let ret = f(&mut value);
+        self.record(value);
+        Some(ret)
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Load<T> {
     Loading,
     Ready(T),
 }
+impl<T> Load<T> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready(_))
+    }
+    pub fn ready(self) -> Option<T> {
+        match self {
+            Self::Loading => None,
+            Self::Ready(v) => Some(v),
+        }
+    }
+    pub fn unwrap_or(self, default: T) -> T {
+        self.ready().unwrap_or(default)
+    }
+
+    pub fn as_ref(&self) -> Load<&T> {
+        match self {
+            Self::Loading => Load::Loading,
+            Self::Ready(v) => Load::Ready(v),
+        }
+    }
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Load<U> {
+        match self {
+            Self::Loading => Load::Loading,
+            Self::Ready(v) => Load::Ready(f(v)),
+        }
+    }
+    pub fn and_then<U>(self, f: impl FnOnce(T) -> Load<U>) -> Load<U> {
+        match self {
+            Self::Loading => Load::Loading,
+            Self::Ready(v) => f(v),
+        }
+    }
+}
+impl<T: IntoView> Load<T> {
+    /// Renders the inner view once ready, and the provided fallback while loading.
+    pub fn view_or<V: IntoView>(self, fallback: impl FnOnce() -> V) -> View {
+        match self {
+            Self::Loading => fallback().into_view(),
+            Self::Ready(v) => v.into_view(),
+        }
+    }
+}
+impl<T: IntoView> IntoView for Load<T> {
+    /// Renders a `div.loading` spinner while loading, see [Load::view_or] for a custom one.
+    fn into_view(self) -> View {
+        self.view_or(|| html::div().class("loading", true))
+    }
+}
+impl<T> From<Option<T>> for Load<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            None => Self::Loading,
+            Some(v) => Self::Ready(v),
+        }
+    }
+}
 
 pub mod rc_signal {
     use leptos::{
@@ -376,7 +1456,7 @@ pub mod rc_signal {
     use std::{
         hash::{Hash, Hasher},
         ops::Deref,
-        rc::Rc,
+        rc::{Rc, Weak},
     };
 
     /// To suppress warning when leaking complex global signals. Use with caution on shallow function.
@@ -401,6 +1481,13 @@ pub mod rc_signal {
         pub fn new(value: T) -> Self {
             with_intentional_leak(|| Self(create_rw_signal(value)))
         }
+        /// Disposes of the underlying signal.
+        ///
+        /// This takes `self` by value, but since the type is [Copy] any other copy of it
+        /// is left dangling and must not be used anymore.
+        pub fn dispose(self) {
+            self.0.dispose();
+        }
     }
     impl<T: 'static> Copy for LeakedRwSignal<T> {}
     impl<T: 'static> Clone for LeakedRwSignal<T> {
@@ -463,6 +1550,36 @@ pub mod rc_signal {
             let _ = store_value(self.clone());
             self.inner.0 .0
         }
+        /// Creates a [WeakRcSignal], which doesn't keep the signal alive.
+        ///
+        /// The underlying signal is disposed of once the last [RcSignal] is dropped,
+        /// regardless of any remaining weak handles.
+        pub fn downgrade(&self) -> WeakRcSignal<T> {
+            WeakRcSignal {
+                inner: Rc::downgrade(&self.inner),
+            }
+        }
+    }
+
+    /// A weak handle to an [RcSignal], useful to break reference cycles.
+    #[derive(Debug)]
+    pub struct WeakRcSignal<T: 'static> {
+        inner: Weak<RcSignalInner<T>>,
+    }
+    impl<T: 'static> WeakRcSignal<T> {
+        /// Returns [None] if the signal has already been disposed of.
+        pub fn upgrade(&self) -> Option<RcSignal<T>> {
+            Some(RcSignal {
+                inner: self.inner.upgrade()?,
+            })
+        }
+    }
+    impl<T: 'static> Clone for WeakRcSignal<T> {
+        fn clone(&self) -> Self {
+            Self {
+                inner: self.inner.clone(),
+            }
+        }
     }
     impl<T: 'static> Clone for RcSignal<T> {
         fn clone(&self) -> Self {
@@ -476,6 +1593,20 @@ pub mod rc_signal {
             self.inner.0.hash(state);
         }
     }
+    /// Serializes as the inner value.
+    #[cfg(feature = "signal-serde")]
+    impl<T: serde::Serialize + 'static> serde::Serialize for RcSignal<T> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.with(|v| v.serialize(serializer))
+        }
+    }
+    /// Deserializes the inner value, and goes through [RcSignal::new] to create the signal.
+    #[cfg(feature = "signal-serde")]
+    impl<'de, T: serde::Deserialize<'de> + 'static> serde::Deserialize<'de> for RcSignal<T> {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Self::new)
+        }
+    }
     impl<T: 'static> SignalWithUntracked for RcSignal<T> {
         type Value = T;
 
@@ -561,3 +1692,157 @@ pub mod rc_signal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_bind_self_normalizing() {
+        let runtime = leptos::create_runtime();
+
+        let parent = create_rw_signal(0_i32);
+        let slot: Rc<Cell<Option<RwSignal<i32>>>> = Rc::default();
+        let child = parent.double_bind(|v| *v, {
+            let slot = slot.clone();
+            move |v| {
+                let normalized = (*v).clamp(0, 10);
+                if normalized != *v {
+                    // Writing back to the child while it is being propagated.
+                    slot.get().unwrap().set(normalized);
+                }
+                normalized
+            }
+        });
+        slot.set(Some(child));
+
+        child.set(20);
+        assert_eq!(parent.get_untracked(), 10);
+        assert_eq!(child.get_untracked(), 10);
+
+        parent.set(5);
+        assert_eq!(child.get_untracked(), 5);
+
+        child.set(-3);
+        assert_eq!(parent.get_untracked(), 0);
+        assert_eq!(child.get_untracked(), 0);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_option_combinators() {
+        let runtime = leptos::create_runtime();
+
+        let source = create_rw_signal(Some(2_i32));
+        let doubled = source.map_some(|v| v * 2);
+        let or_zero = source.unwrap_or(0);
+        let or_else = source.unwrap_or_else(|| -1);
+        assert_eq!(doubled.get_untracked(), Some(4));
+        assert_eq!(or_zero.get_untracked(), 2);
+
+        source.set(None);
+        assert_eq!(doubled.get_untracked(), None);
+        assert_eq!(or_zero.get_untracked(), 0);
+        assert_eq!(or_else.get_untracked(), -1);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_map_indexed() {
+        let runtime = leptos::create_runtime();
+
+        let source = create_rw_signal(vec!['a', 'b']);
+        let indexed = source.map_indexed(|i, c| format!("{i}{c}"));
+        assert_eq!(indexed.get_untracked(), vec!["0a", "1b"]);
+
+        source.set(vec![]);
+        assert_eq!(indexed.get_untracked(), Vec::<String>::new());
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_pulse_restarts() {
+        let runtime = leptos::create_runtime();
+
+        // Timers need a browser, so this drives the pulses without [WriteSignalExt::pulse].
+        let flag = create_rw_signal(false);
+        let first = start_pulse(&flag);
+        assert!(flag.get_untracked());
+
+        let second = start_pulse(&flag);
+        assert!(end_pulse(first).is_none(), "the first reset was cancelled");
+        assert!(flag.get_untracked());
+
+        let other = create_rw_signal(false);
+        let unrelated = start_pulse(&other);
+        assert!(end_pulse(second).is_some());
+        assert!(end_pulse(unrelated).is_some());
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_history_only_notifies_on_changes() {
+        let runtime = leptos::create_runtime();
+
+        let history = History::new(0, 10);
+        let undo_changes = history.can_undo().count_changes();
+        let redo_changes = history.can_redo().count_changes();
+        history.undo();
+        history.redo();
+        assert_eq!(undo_changes.get_untracked(), 0);
+        assert_eq!(redo_changes.get_untracked(), 0);
+
+        history.set(1);
+        assert_eq!(undo_changes.get_untracked(), 1);
+        assert_eq!(redo_changes.get_untracked(), 0);
+
+        history.undo();
+        assert_eq!(history.get_untracked(), 0);
+        assert_eq!(undo_changes.get_untracked(), 2);
+        assert_eq!(redo_changes.get_untracked(), 1);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn test_signal_bag_disposes_removed() {
+        use super::rc_signal::RcSignal;
+
+        let runtime = leptos::create_runtime();
+
+        let bag = SignalBag::new();
+        let signal = RcSignal::new(1_u32);
+        let weak = signal.downgrade();
+        let handle = bag.push(signal);
+        let sum = bag.sum();
+        assert_eq!(sum.get_untracked(), 1);
+        assert!(weak.upgrade().is_some());
+
+        bag.remove(handle);
+        assert_eq!(sum.get_untracked(), 0);
+        assert!(weak.upgrade().is_none());
+
+        runtime.dispose();
+    }
+
+    #[cfg(feature = "signal-serde")]
+    #[test]
+    fn test_rc_signal_serde_round_trip() {
+        use super::rc_signal::RcSignal;
+
+        let runtime = leptos::create_runtime();
+        {
+            let signal = RcSignal::new(vec![1_u32, 2, 3]);
+            let json = serde_json::to_string(&signal).unwrap();
+            assert_eq!(json, "[1,2,3]");
+
+            let back: RcSignal<Vec<u32>> = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.get_untracked(), vec![1, 2, 3]);
+        }
+        runtime.dispose();
+    }
+}
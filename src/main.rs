@@ -1,35 +1,39 @@
-// pub mod human;
+pub mod bookmarks;
+pub mod data;
+pub mod fuzzy;
+pub mod human;
 pub mod leptos_ext;
+pub mod markdown;
+pub mod media;
+pub mod theme;
 pub mod visibility;
 
+use chrono::Local;
 use leptos::{
-    ev, html, mount_to_body, HtmlElement, IntoView, RwSignal, SignalGet, SignalGetUntracked,
-    SignalSet, View,
+    batch, create_memo, create_node_ref, ev, event_target_value, html, mount_to_body, set_timeout,
+    wasm_bindgen::{JsCast, JsValue},
+    window_event_listener, HtmlElement, IntoView, NodeRef, RwSignal, Signal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, SignalWithUntracked, View,
 };
-use serde::{Deserialize, Serialize};
 use std::{
-    cell::RefCell,
     collections::{BTreeMap, BTreeSet},
-    ops::Deref,
-    rc::Rc,
+    ops::{Deref, Range},
+    time::Duration,
 };
 
+pub use self::data::Datapoint;
+
 use self::{
-    leptos_ext::{ReadSignalExt, WriteSignalExt},
-    visibility::{ViewportSize, Visibility},
+    bookmarks::bookmarks,
+    data::{truncate, Data, Entry},
+    fuzzy::fuzzy_match,
+    human::{format_count, format_with_uncertainty, prefix_datapoints, round_with_power_default},
+    leptos_ext::{rc_signal::RcSignal, History, ReadSignalExt, WriteSignalExt},
+    media::prefers_reduced_motion,
+    theme::{theme, Theme},
+    visibility::{first_visible, scroll_into_view_centered, Axis, ViewportSize, Visibility},
 };
 
-#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
-struct Data {
-    entries: BTreeMap<u128, Entry>,
-    children: BTreeMap<u128, BTreeSet<u128>>,
-}
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-struct Entry {
-    text: String,
-    parents: Vec<u128>,
-}
-
 pub fn main() {
     console_log::init().unwrap();
 
@@ -39,58 +43,656 @@ pub fn main() {
 }
 
 fn app() -> impl IntoView {
-    let current = RwSignal::new(999);
-    let data = RwSignal::new(initial_data());
+    let data = History::new(Data::load_local().unwrap_or_else(initial_data), 100);
+    let current = RwSignal::new(hash_node().unwrap_or_else(|| {
+        data.with_untracked(|d| d.roots().first().copied().unwrap_or_default())
+    }));
+
+    current.for_each_after_first(|&id| set_hash_node(id));
+    let recent = RcSignal::new(vec![current.get_untracked()]);
+    current.for_each_after_first({
+        let recent = recent.clone();
+        move |&id| recent.update(|recent| visit(recent, id))
+    });
+    let _ = window_event_listener(ev::hashchange, move |_| {
+        if let Some(id) = hash_node() {
+            current.set_if_changed(id);
+        }
+    });
+
+    // Only written once edits settle, so bursts of changes don't thrash storage.
+    data.debounce(1000).for_each_after_first(Data::save_local);
+
+    let _ = window_event_listener(ev::keydown, move |e| {
+        if is_typing(&e) || !(e.ctrl_key() || e.meta_key()) {
+            return;
+        }
+        match e.key().to_lowercase().as_str() {
+            "z" if e.shift_key() => data.redo(),
+            "z" => data.undo(),
+            "y" => data.redo(),
+            _ => return,
+        }
+        e.prevent_default();
+    });
+
+    let bookmarks = bookmarks().into_rw();
+    // Deleted entries can't be jumped to anymore.
+    data.for_each(move |data| {
+        let missing = |ids: &BTreeSet<u128>| ids.iter().any(|&id| data.get(id).is_none());
+        if bookmarks.with_untracked(missing) {
+            bookmarks.update(|ids| ids.retain(|&id| data.get(id).is_some()));
+        }
+    });
+    // Undoing the creation of the current entry would otherwise leave nothing to show.
+    data.for_each_window(move |old, new| {
+        let id = current.get_untracked();
+        if new.get(id).is_some() {
+            return;
+        }
+        let parent = old.parents(id).find(|&p| new.get(p).is_some());
+        let root = || new.roots().first().copied().unwrap_or_default();
+        current.set(parent.unwrap_or_else(root));
+    });
+
+    let selection = Selection::new();
+    let axis = RwSignal::new(Axis::default());
+    let focus = RwSignal::new(None::<u128>);
+    let search_input = create_node_ref::<html::Input>();
+    let help = RwSignal::new(false);
+    let _ = window_event_listener(ev::keydown, move |e| {
+        if is_typing(&e) || e.ctrl_key() || e.meta_key() || e.alt_key() {
+            return;
+        }
+        let id = current.get_untracked();
+        let key = e.key();
+        match key.as_str() {
+            "ArrowLeft" | "ArrowRight" => {
+                let parents: Vec<_> = data.with_untracked(|data| data.parents(id).collect());
+                let Some(active) = data.with_untracked(|data| selection.parent(data, id)) else {
+                    return;
+                };
+                let i = parents.iter().position(|&p| p == active).unwrap_or(0);
+                let i = match key.as_str() {
+                    "ArrowLeft" => i.checked_sub(1).unwrap_or(parents.len() - 1),
+                    _ => (i + 1) % parents.len(),
+                };
+                selection.set_parent(id, parents[i]);
+            }
+            "ArrowUp" | "ArrowDown" => {
+                let next = data.with_untracked(|data| match key.as_str() {
+                    "ArrowUp" => selection.parent(data, id),
+                    _ => selection.child(data, id),
+                });
+                if let Some(next) = next {
+                    current.set(next);
+                    scroll_to_current(axis.get_untracked());
+                }
+            }
+            "/" => {
+                if let Some(input) = search_input.get_untracked() {
+                    let _ = input.focus();
+                }
+            }
+            "?" => help.update(|open| *open = !*open),
+            "Escape" if help.get_untracked() => help.set(false),
+            _ => return,
+        }
+        e.prevent_default();
+    });
+
+    html::div()
+        .child(breadcrumbs(current, data, selection, axis))
+        .child(
+            html::div()
+                .class("tools", true)
+                .child(root_picker(current, data, selection))
+                .child(search(current, data, axis, search_input))
+                .child(jump_to_id(current))
+                .child(import_data(current, data))
+                .child(export_data(data))
+                .child(toggle_axis(axis))
+                .child(toggle_theme())
+                .child(clear_focus(focus))
+                .child(si_prefixes())
+                .child(recently_visited(recent, current, data, axis))
+                .child(bookmark_list(bookmarks, current, data, axis)),
+        )
+        .child(minimap(data, current, selection))
+        .child(shortcuts(help))
+        .child(
+            html::div()
+                .class("graph", true)
+                .class("vertical", move || axis.get() == Axis::Vertical)
+                .child(graph(current, data, selection, axis, focus, bookmarks)),
+        )
+}
+fn breadcrumbs(
+    current: RwSignal<u128>,
+    data: History<Data>,
+    selection: Selection,
+    axis: RwSignal<Axis>,
+) -> impl IntoView {
+    const MAX_CHARS: usize = 40;
+
+    move || {
+        let crumbs: Vec<_> = data.with(|data| {
+            let mut path = selection.upstream(data, current.get());
+            path.reverse();
+            path.into_iter()
+                .map(|id| {
+                    let text = data
+                        .get(id)
+                        .map(|e| truncate(&e.text, MAX_CHARS))
+                        .unwrap_or_default();
+                    html::li()
+                        .on(ev::click, move |_| {
+                            current.set(id);
+                            scroll_to_current(axis.get_untracked());
+                        })
+                        .child(text)
+                })
+                .collect()
+        });
+        html::ol().class("breadcrumbs", true).child(crumbs)
+    }
+}
+/// Moves `id` to the front of the recently visited list, dropping the oldest past the limit.
+fn visit(recent: &mut Vec<u128>, id: u128) {
+    const MAX_RECENT: usize = 20;
+
+    recent.retain(|&r| r != id);
+    recent.insert(0, id);
+    recent.truncate(MAX_RECENT);
+}
+/// Entries visited this session, most recent first.
+fn recently_visited(
+    recent: RcSignal<Vec<u128>>,
+    current: RwSignal<u128>,
+    data: History<Data>,
+    axis: RwSignal<Axis>,
+) -> impl IntoView {
+    html::details()
+        .class("recent", true)
+        .child(html::summary().child("Recently visited"))
+        .child(jump_list(move || recent.get(), current, data, axis))
+}
+/// The pinned entries, in id order.
+fn bookmark_list(
+    bookmarks: RwSignal<BTreeSet<u128>>,
+    current: RwSignal<u128>,
+    data: History<Data>,
+    axis: RwSignal<Axis>,
+) -> impl IntoView {
+    let ids = move || bookmarks.with(|ids| ids.iter().copied().collect());
+    html::details()
+        .class("bookmarks", true)
+        .child(html::summary().child("Bookmarks"))
+        .child(jump_list(ids, current, data, axis))
+}
+/// The text of each of `ids`, moving to it when clicked. Missing entries are skipped.
+fn jump_list(
+    ids: impl Fn() -> Vec<u128> + 'static,
+    current: RwSignal<u128>,
+    data: History<Data>,
+    axis: RwSignal<Axis>,
+) -> HtmlElement<html::Ol> {
+    const MAX_CHARS: usize = 40;
+
+    let items = move || {
+        let ids = ids();
+        data.with(|data| {
+            ids.into_iter()
+                .filter_map(|id| Some((id, truncate(&data.get(id)?.text, MAX_CHARS))))
+                .map(|(id, text)| {
+                    html::li()
+                        .on(ev::click, move |_| {
+                            current.set(id);
+                            scroll_to_current(axis.get_untracked());
+                        })
+                        .child(text)
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+    html::ol().child(items)
+}
+/// Lets the user start from another root, when there is more than one.
+fn root_picker(
+    current: RwSignal<u128>,
+    data: History<Data>,
+    selection: Selection,
+) -> impl IntoView {
+    const MAX_CHARS: usize = 40;
+
+    let roots = create_memo(move |_| {
+        data.with(|data| {
+            let text = |id| data.get(id).map(|e| truncate(&e.text, MAX_CHARS));
+            data.roots()
+                .into_iter()
+                .map(|id| (id, text(id).unwrap_or_default()))
+                .collect::<Vec<_>>()
+        })
+    });
+    // The root at the top of the active path, if it isn't a cycle.
+    let root = move || {
+        data.with(|data| {
+            let path = selection.upstream(data, current.get());
+            path.last().map(u128::to_string).unwrap_or_default()
+        })
+    };
+
+    move || {
+        let roots = roots.get();
+        (roots.len() > 1).then(|| {
+            let options: Vec<_> = roots
+                .into_iter()
+                .map(|(id, text)| html::option().attr("value", id).child(text))
+                .collect();
+            html::select()
+                .attr("title", "Start from")
+                .child(options)
+                .prop("value", root)
+                .on(ev::change, move |e| {
+                    if let Ok(id) = event_target_value(&e).parse() {
+                        current.set(id);
+                    }
+                })
+        })
+    }
+}
+fn search(
+    current: RwSignal<u128>,
+    data: History<Data>,
+    axis: RwSignal<Axis>,
+    input: NodeRef<html::Input>,
+) -> impl IntoView {
+    const MAX_RESULTS: usize = 20;
+    /// Below this many exact matches, fuzzy ones are listed after them.
+    const MIN_EXACT: usize = 5;
+
+    let query = RwSignal::new(String::new());
+    let debounced = query.debounce(200);
+
+    let results = move || {
+        let query = debounced.get();
+        let query = query.trim();
+        if query.is_empty() {
+            return None;
+        }
+        let results: Vec<_> = data.with(|data| {
+            let mut found: Vec<(u128, Vec<Range<usize>>)> = data
+                .entries()
+                .iter()
+                .filter_map(|(&id, entry)| Some((id, vec![find_ignore_case(&entry.text, query)?])))
+                .take(MAX_RESULTS)
+                .collect();
+            if found.len() < MIN_EXACT {
+                let mut fuzzy: Vec<_> = data
+                    .entries()
+                    .iter()
+                    .filter(|(id, _)| !found.iter().any(|(f, _)| f == *id))
+                    .filter_map(|(&id, entry)| Some((id, fuzzy_match(&entry.text, query)?)))
+                    .collect();
+                // Stable, so ties stay in id order.
+                fuzzy.sort_by_key(|(_, found)| std::cmp::Reverse(found.score));
+                let fuzzy = fuzzy.into_iter().map(|(id, found)| (id, found.ranges));
+                found.extend(fuzzy.take(MAX_RESULTS - found.len()));
+            }
+            found
+                .into_iter()
+                .map(|(id, found)| {
+                    search_result(id, &data.get(id).unwrap().text, &found, current, axis)
+                })
+                .collect()
+        });
+        Some(html::ul().class("search-results", true).child(results))
+    };
+
+    html::div()
+        .child(
+            html::input()
+                .node_ref(input)
+                .attr("type", "search")
+                .attr("placeholder", "Search (/)")
+                .prop("value", move || query.get())
+                .on(ev::input, move |e| query.set(event_target_value(&e))),
+        )
+        .child(results)
+}
+/// A list of the keyboard shortcuts, shown while `open`.
+fn shortcuts(open: RwSignal<bool>) -> impl IntoView {
+    const SHORTCUTS: &[(&str, &str)] = &[
+        ("↑ / ↓", "Move to the active parent or child"),
+        ("← / →", "Switch the active parent"),
+        ("/", "Search"),
+        ("Ctrl+Z", "Undo"),
+        ("Ctrl+Shift+Z or Ctrl+Y", "Redo"),
+        ("Double click", "Edit an entry"),
+        ("Escape", "Stop editing, or close this"),
+        ("?", "Show or hide this"),
+    ];
 
-    html::div().class("graph", true).child(graph(current, data))
+    move || {
+        open.get().then(|| {
+            let rows: Vec<_> = SHORTCUTS
+                .iter()
+                .map(|&(keys, action)| {
+                    html::tr()
+                        .child(html::td().child(html::kbd().child(keys)))
+                        .child(html::td().child(action))
+                })
+                .collect();
+            html::div()
+                .class("shortcuts", true)
+                .on(ev::click, move |_| open.set(false))
+                .child(html::table().child(rows))
+        })
+    }
+}
+/// `text` with the `found` byte ranges highlighted, which must be sorted and not overlap.
+fn search_result(
+    id: u128,
+    text: &str,
+    found: &[Range<usize>],
+    current: RwSignal<u128>,
+    axis: RwSignal<Axis>,
+) -> HtmlElement<html::Li> {
+    let mut li = html::li().on(ev::click, move |_| {
+        current.set(id);
+        scroll_to_current(axis.get_untracked());
+    });
+    let mut end = 0;
+    for found in found {
+        li = li
+            .child(text[end..found.start].to_owned())
+            .child(html::mark().child(text[found.clone()].to_owned()));
+        end = found.end;
+    }
+    li.child(text[end..].to_owned())
+}
+fn jump_to_id(current: RwSignal<u128>) -> impl IntoView {
+    let raw = RwSignal::new(String::new());
+    let invalid = move || {
+        let raw = raw.get();
+        !raw.trim().is_empty() && raw.trim().parse::<u128>().is_err()
+    };
+
+    html::input()
+        .attr("placeholder", "Go to id")
+        .attr("inputmode", "numeric")
+        .class("invalid", invalid)
+        .prop("value", move || raw.get())
+        .on(ev::input, move |e| raw.set(event_target_value(&e)))
+        .on(ev::keydown, move |e| {
+            if e.key() != "Enter" {
+                return;
+            }
+            if let Ok(id) = raw.get_untracked().trim().parse() {
+                current.set(id);
+            }
+        })
+}
+fn toggle_axis(axis: RwSignal<Axis>) -> impl IntoView {
+    html::button()
+        .child(move || match axis.get() {
+            Axis::Horizontal => "Vertical layout",
+            Axis::Vertical => "Horizontal layout",
+        })
+        .on(ev::click, move |_| {
+            axis.update(|axis| {
+                *axis = match axis {
+                    Axis::Horizontal => Axis::Vertical,
+                    Axis::Vertical => Axis::Horizontal,
+                }
+            })
+        })
 }
-fn graph(current: RwSignal<u128>, data: RwSignal<Data>) -> impl IntoView {
+fn toggle_theme() -> impl IntoView {
+    let theme = theme();
+    html::button()
+        .child(move || match theme.get() {
+            Theme::Light => "Dark mode",
+            Theme::Dark => "Light mode",
+        })
+        .on(ev::click, move |_| {
+            theme.update(|theme| *theme = theme.toggled())
+        })
+}
+fn clear_focus(focus: RwSignal<Option<u128>>) -> impl IntoView {
+    move || {
+        focus.get().map(|_| {
+            html::button()
+                .child("Show everything")
+                .on(ev::click, move |_| focus.set(None))
+        })
+    }
+}
+fn export_data(data: History<Data>) -> impl IntoView {
+    html::button().child("Export").on(ev::click, move |_| {
+        if let Err(e) = download("graph.json", &data.with_untracked(Data::to_json)) {
+            log::warn!("Failed to export data: {e:?}");
+        }
+    })
+}
+fn import_data(current: RwSignal<u128>, data: History<Data>) -> impl IntoView {
+    let raw = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+
+    let import = move |_: ev::MouseEvent| match Data::from_json(&raw.get_untracked()) {
+        Ok(new) => batch(|| {
+            if new.get(current.get_untracked()).is_none() {
+                current.set(new.roots().first().copied().unwrap_or_default());
+            }
+            data.set(new);
+            raw.set(String::new());
+            error.set(None);
+        }),
+        Err(e) => error.set(Some(e.to_string())),
+    };
+
+    html::details()
+        .child(html::summary().child("Import"))
+        .child(
+            html::textarea()
+                .attr("placeholder", "Paste a graph as JSON")
+                .prop("value", move || raw.get())
+                .on(ev::input, move |e| raw.set(event_target_value(&e))),
+        )
+        .child(html::button().child("Import").on(ev::click, import))
+        .child(move || {
+            error
+                .get()
+                .map(|e| html::pre().class("error", true).child(e))
+        })
+}
+fn si_prefixes() -> impl IntoView {
+    let points: Vec<_> = prefix_datapoints()
+        .iter()
+        .map(|p| datapoint(p, ""))
+        .collect();
+    html::details()
+        .child(html::summary().child("SI prefixes"))
+        .child(html::ul().class("datapoints", true).child(points))
+}
+fn datapoint(point: &Datapoint, unit: &str) -> HtmlElement<html::Li> {
+    let refs: Vec<_> = point
+        .refs
+        .iter()
+        .map(|url| html::a().attr("href", url.clone()).child(url.clone()))
+        .collect();
+
+    html::li()
+        .class("datapoint", true)
+        .attr("title", round_with_power_default(point.size, unit))
+        .child(html::span().class("name", true).child(point.name.clone()))
+        .child(
+            html::span()
+                .class("size", true)
+                .child(format_with_uncertainty(point, unit)),
+        )
+        .child(point.comment.clone().map(|c| html::p().child(c)))
+        .child(refs)
+}
+/// Every entry as a dot, roots at the top, so the whole graph fits in a corner.
+fn minimap(data: History<Data>, current: RwSignal<u128>, selection: Selection) -> impl IntoView {
+    const STEP: usize = 8;
+
+    let active_path = selection.active_path(data, current);
+    let layers = create_memo(move |_| {
+        data.with(|data| {
+            let titled = |id| (id, data.get(id).map(|e| truncate(&e.text, 40)));
+            let layers = data.layers().ok()?;
+            let layers = layers
+                .into_iter()
+                .map(|layer| layer.into_iter().map(titled));
+            Some(layers.map(Iterator::collect).collect::<Vec<Vec<_>>>())
+        })
+    });
+
+    move || {
+        let layers = layers.get()?;
+        let dot = |id: u128, title: Option<String>, x: usize, y: usize| {
+            html::span()
+                .class("dot", true)
+                .class("current", move || current.get() == id)
+                .class("on-path", move || active_path.with(|p| p.contains(&id)))
+                .style("left", format!("{}px", x * STEP))
+                .style("top", format!("{}px", y * STEP))
+                .attr("title", title)
+                .on(ev::click, move |_| current.set(id))
+        };
+        let dots: Vec<_> = layers
+            .iter()
+            .enumerate()
+            .flat_map(|(y, layer)| layer.iter().enumerate().map(move |(x, e)| (e, x, y)))
+            .map(|((id, title), x, y)| dot(*id, title.clone(), x, y))
+            .collect();
+
+        let width = layers.iter().map(Vec::len).max().unwrap_or(0) * STEP;
+        Some(
+            html::div().class("minimap", true).child(
+                html::div()
+                    .class("dots", true)
+                    .style("width", format!("{width}px"))
+                    .style("height", format!("{}px", layers.len() * STEP))
+                    .child(dots),
+            ),
+        )
+    }
+}
+fn graph(
+    current: RwSignal<u128>,
+    data: History<Data>,
+    selection: Selection,
+    axis: RwSignal<Axis>,
+    focus: RwSignal<Option<u128>>,
+    bookmarks: RwSignal<BTreeSet<u128>>,
+) -> impl IntoView {
+    /// Rows shown on each side of the current entry before asking to show more.
+    const DEFAULT_MAX_DEPTH: usize = 20;
+
+    let focused = create_memo(move |_| {
+        let id = focus.get()?;
+        Some(data.with(|data| data.lineage(id)))
+    });
+    let ctx = GraphCtx {
+        current,
+        data,
+        selection,
+        axis,
+        collapsed: RwSignal::new(BTreeSet::new()),
+        focus,
+        bookmarks,
+        max_depth: RwSignal::new(DEFAULT_MAX_DEPTH),
+        viewport: ViewportSize::signal(),
+        active_path: selection.active_path(data, current),
+        editing: RwSignal::new(None),
+    };
+
     move || {
         let initial = current.get();
-        let data = data.get();
+        let data = match focused.get() {
+            // Moving away from the focused lineage shows everything again, rather than nothing.
+            Some(ids) if ids.contains(&initial) => data.with(|data| data.subgraph(&ids)),
+            _ => data.get(),
+        };
+        // Rows are built for one axis, so rebuild everything when it changes.
+        axis.track();
 
-        match data.entries.get(&initial) {
+        match data.get(initial) {
             Some(entry) => [
                 html::div()
+                    .class("spacer", true)
                     .style("width", "100%")
                     .style("height", "40px")
                     .into_view(),
-                graph_upstream(initial, data.clone(), BTreeSet::new()).into_view(),
-                card(initial, entry).class("current", true).into_view(),
-                graph_downstream(initial, data, BTreeSet::new()).into_view(),
+                {
+                    let data = data.clone();
+                    move || graph_upstream(initial, data.clone(), ctx, BTreeSet::new(), 0)
+                }
+                .into_view(),
+                card(initial, entry, data.children(initial).count(), ctx)
+                    .class("current", true)
+                    .into_view(),
+                {
+                    let data = data.clone();
+                    move || graph_downstream(initial, data.clone(), ctx, BTreeSet::new(), 0)
+                }
+                .into_view(),
                 explanation().into_view(),
                 html::div()
+                    .class("spacer", true)
                     .style("width", "100%")
                     .style("height", "150vh")
                     .into_view(),
             ]
             .into_view(),
-            None => empty_card(initial, "No initial value").into_view(),
+            None => empty_card(initial, "No entry").into_view(),
         }
     }
 }
-fn graph_upstream(child: u128, data: Data, mut done: BTreeSet<u128>) -> impl IntoView {
-    let Some(entry) = data.entries.get(&child).cloned() else {
+fn graph_upstream(
+    child: u128,
+    data: Data,
+    ctx: GraphCtx,
+    mut done: BTreeSet<u128>,
+    depth: usize,
+) -> impl IntoView {
+    let Some(entry) = data.get(child).cloned() else {
         return "Missing entry".into_view();
     };
 
     if done.contains(&child) {
         return "Repeated".into_view();
     }
+    if depth >= ctx.max_depth.get_untracked() {
+        return depth_limit(depth, ctx, move || {
+            graph_upstream(child, data.clone(), ctx, done.clone(), depth).into_view()
+        });
+    }
     done.insert(child);
 
+    if ctx.collapsed.with(|collapsed| collapsed.contains(&child)) {
+        return collapsed_indicator(child, data.ancestors(child).len(), ctx);
+    }
+
     let Some(first) = entry.parents.first().cloned() else {
         return View::default();
     };
-    let current_parent = RwSignal::new(first);
-
     let parent_ids: Vec<_> = entry.parents.clone();
+    let current_parent = ctx.selection.parents.map_dedup({
+        let parent_ids = parent_ids.clone();
+        move |selected| pick(selected, child, &parent_ids).unwrap_or(first)
+    });
+
     let parents: Vec<_> = entry
         .parents
         .clone()
         .into_iter()
-        .map(|p| match data.entries.get(&p) {
-            Some(entry) => card(p, entry).class("current", move || current_parent.get() == p),
+        .map(|p| match data.get(p) {
+            Some(entry) => card(p, entry, data.children(p).count(), ctx)
+                .class("current", move || current_parent.get() == p),
             None => empty_card(p, "Missing parent"),
         })
         .collect();
@@ -98,9 +700,49 @@ fn graph_upstream(child: u128, data: Data, mut done: BTreeSet<u128>) -> impl Int
     let is_single = parents.len() == 1;
 
     let spacer = RwSignal::new(0.);
+    let axis = ctx.axis.get_untracked();
+
+    let scrolled = RwSignal::new(());
+    scrolled.throttle_animation_frame().for_each_after_first({
+        let parent_ids = parent_ids.clone();
+        let parents = parents.clone();
+        move |()| {
+            let (first_id, first_e) =
+                first_visible_element(&parent_ids, &parents, &ctx.viewport.get_untracked(), axis);
+
+            if current_parent.get_untracked() != first_id {
+                let top = first_e.get_bounding_client_rect().top();
+                ctx.selection.set_parent(child, first_id);
+                // Columns are of fixed width, so only rows shift things around.
+                if axis == Axis::Horizontal {
+                    restore_position(top, first_e, spacer);
+                }
+            }
+        }
+    });
+
+    let row = html::div()
+        .class("row", true)
+        .class("single", is_single)
+        .on(ev::scroll, move |_| scrolled.set(()))
+        .child(parents.clone());
+    current_parent.for_each_after_first({
+        let row = row.clone();
+        move |&id| {
+            scroll_row_to(
+                &row,
+                &parent_ids,
+                &parents,
+                id,
+                &ctx.viewport.get_untracked(),
+                axis,
+            )
+        }
+    });
 
     [
         html::div()
+            .class("spacer", true)
             .style("width", "100%")
             .style("height", spacer.map_dedup(|v| format!("{v}px")))
             .into_view(),
@@ -108,108 +750,130 @@ fn graph_upstream(child: u128, data: Data, mut done: BTreeSet<u128>) -> impl Int
             let data = data.clone();
             move || {
                 let current_parent = current_parent.get();
-                graph_upstream(current_parent, data.clone(), done.clone())
+                graph_upstream(current_parent, data.clone(), ctx, done.clone(), depth + 1)
             }
         }
         .into_view(),
-        html::div()
-            .class("row", true)
-            .class("single", is_single)
-            .on(ev::scroll, {
-                let parents = parents.clone();
-                let parent_ids = parent_ids.clone();
-                let frame = Rc::new(RefCell::new(None));
-                move |_| {
-                    let parents = parents.clone();
-                    let parent_ids = parent_ids.clone();
-
-                    let inner = frame.clone();
-                    let new = frame.take().unwrap_or_else(move || {
-                        gloo_render::request_animation_frame(move |_| {
-                            let (first_id, first_e) = first_visible_element(&parent_ids, &parents);
-
-                            if current_parent.get_untracked() != first_id {
-                                let top = first_e.get_bounding_client_rect().top();
-                                current_parent.set(first_id);
-                                restore_position(top, first_e, spacer);
-                            }
-
-                            drop(inner.take());
-                        })
-                    });
-                    frame.replace(Some(new));
-                }
-            })
-            .child(parents)
-            .into_view(),
+        row.into_view(),
     ]
     .into_view()
 }
-fn graph_downstream(parent: u128, data: Data, mut done: BTreeSet<u128>) -> impl IntoView {
+/// Stands in for the rows past the depth limit, raising it on click.
+///
+/// Renders `rest` instead once the limit is past `depth`, so only what is newly shown is built.
+fn depth_limit(depth: usize, ctx: GraphCtx, rest: impl Fn() -> View + 'static) -> View {
+    const STEP: usize = 20;
+
+    (move || {
+        if depth < ctx.max_depth.get() {
+            return rest();
+        }
+        html::button()
+            .class("show-more", true)
+            .child(format!("Show {STEP} more"))
+            .on(ev::click, move |_| ctx.max_depth.update(|max| *max += STEP))
+            .into_view()
+    })
+    .into_view()
+}
+/// Stands in for the `hidden` entries past the collapsed `id`, expanding it on click.
+fn collapsed_indicator(id: u128, hidden: usize, ctx: GraphCtx) -> View {
+    if hidden == 0 {
+        return View::default();
+    }
+    html::button()
+        .class("hidden-count", true)
+        .child(format!("+{hidden} hidden"))
+        .on(ev::click, move |_| ctx.toggle_collapsed(id))
+        .into_view()
+}
+fn graph_downstream(
+    parent: u128,
+    data: Data,
+    ctx: GraphCtx,
+    mut done: BTreeSet<u128>,
+    depth: usize,
+) -> impl IntoView {
     if done.contains(&parent) {
         return "Repeated".into_view();
     }
+    if depth >= ctx.max_depth.get_untracked() {
+        return depth_limit(depth, ctx, move || {
+            graph_downstream(parent, data.clone(), ctx, done.clone(), depth).into_view()
+        });
+    }
     done.insert(parent);
 
-    let Some(_) = data.entries.get(&parent).cloned() else {
+    if ctx.collapsed.with(|collapsed| collapsed.contains(&parent)) {
+        return collapsed_indicator(parent, data.descendants(parent).len(), ctx);
+    }
+
+    let Some(_) = data.get(parent).cloned() else {
         return "Missing entry".into_view();
     };
 
-    let child_ids: Vec<_> = data
-        .children
-        .get(&parent)
-        .cloned()
-        .unwrap_or_default()
-        .into_iter()
-        .collect();
+    let child_ids: Vec<_> = data.children(parent).collect();
 
     let Some(first) = child_ids.first().cloned() else {
         return View::default();
     };
-    let current_child = RwSignal::new(first);
+    let current_child = ctx.selection.children.map_dedup({
+        let child_ids = child_ids.clone();
+        move |selected| pick(selected, parent, &child_ids).unwrap_or(first)
+    });
 
     let children: Vec<_> = child_ids
         .clone()
         .into_iter()
-        .map(|c| match data.entries.get(&c) {
-            Some(entry) => card(c, entry).class("current", move || current_child.get() == c),
+        .map(|c| match data.get(c) {
+            Some(entry) => card(c, entry, data.children(c).count(), ctx)
+                .class("current", move || current_child.get() == c),
             None => empty_card(c, "Missing child"),
         })
         .collect();
 
     let is_single = child_ids.len() == 1;
+    let axis = ctx.axis.get_untracked();
+
+    let scrolled = RwSignal::new(());
+    scrolled.throttle_animation_frame().for_each_after_first({
+        let child_ids = child_ids.clone();
+        let children = children.clone();
+        move |()| {
+            let (first_id, _) =
+                first_visible_element(&child_ids, &children, &ctx.viewport.get_untracked(), axis);
+            if current_child.get_untracked() != first_id {
+                ctx.selection.set_child(parent, first_id);
+            }
+        }
+    });
+
+    let row = html::div()
+        .class("row", true)
+        .class("single", is_single)
+        .on(ev::scroll, move |_| scrolled.set(()))
+        .child(children.clone());
+    current_child.for_each_after_first({
+        let row = row.clone();
+        move |&id| {
+            scroll_row_to(
+                &row,
+                &child_ids,
+                &children,
+                id,
+                &ctx.viewport.get_untracked(),
+                axis,
+            )
+        }
+    });
 
     [
-        html::div()
-            .class("row", true)
-            .class("single", is_single)
-            .on(ev::scroll, {
-                let children = children.clone();
-                let child_ids = child_ids.clone();
-                let frame = Rc::new(RefCell::new(None));
-                move |_| {
-                    let children = children.clone();
-                    let child_ids = child_ids.clone();
-
-                    let inner = frame.clone();
-                    let new = frame.take().unwrap_or_else(move || {
-                        gloo_render::request_animation_frame(move |_| {
-                            let (first_id, first_e) = first_visible_element(&child_ids, &children);
-                            current_child.set_if_changed(first_id);
-
-                            drop(inner.take());
-                        })
-                    });
-                    frame.replace(Some(new));
-                }
-            })
-            .child(children)
-            .into_view(),
+        row.into_view(),
         {
             let data = data.clone();
             move || {
                 let current_child = current_child.get();
-                graph_downstream(current_child, data.clone(), done.clone())
+                graph_downstream(current_child, data.clone(), ctx, done.clone(), depth + 1)
             }
         }
         .into_view(),
@@ -217,22 +881,319 @@ fn graph_downstream(parent: u128, data: Data, mut done: BTreeSet<u128>) -> impl
     .into_view()
 }
 
+/// What every level of the graph needs, besides the data snapshot.
+///
+/// Edits change the data in a single update, batched with any other signals they set,
+/// so the graph is only rebuilt once per edit.
+#[derive(Debug, Clone, Copy)]
+struct GraphCtx {
+    current: RwSignal<u128>,
+    data: History<Data>,
+    selection: Selection,
+    /// The direction rows scroll in.
+    axis: RwSignal<Axis>,
+    /// Entries whose parents and children aren't shown, by id so it survives navigation.
+    collapsed: RwSignal<BTreeSet<u128>>,
+    /// Only the lineage of this entry is shown, if set.
+    focus: RwSignal<Option<u128>>,
+    /// Entries pinned for quick access.
+    bookmarks: RwSignal<BTreeSet<u128>>,
+    /// How many rows are shown above and below the current entry.
+    max_depth: RwSignal<usize>,
+    /// The size of the window, for visibility checks.
+    viewport: Signal<ViewportSize>,
+    /// The entries along the active path through `current`, both up and down.
+    active_path: Signal<BTreeSet<u128>>,
+    /// The entry whose text is being edited.
+    editing: RwSignal<Option<u128>>,
+}
+impl GraphCtx {
+    /// Adds an empty child to `parent`, and starts editing it.
+    fn add_child(self, parent: u128) {
+        let new_child = |data: &mut Data| data.add_entry(String::new(), vec![parent]);
+        let added = batch(|| {
+            let child = self.data.try_update(new_child)?;
+            self.current.set(child);
+            self.editing.set(Some(child));
+            Some(())
+        });
+        if added.is_none() {
+            return;
+        }
+        scroll_to_current(self.axis.get_untracked());
+    }
+    /// Adds an empty parent to `child`, and starts editing it.
+    fn add_parent(self, child: u128) {
+        let new_parent = |data: &mut Data| {
+            data.get(child)?;
+            let parent = data.add_entry(String::new(), vec![]);
+            data.add_parent(child, parent).ok()?;
+            Some(parent)
+        };
+        let added = batch(|| {
+            let parent = self.data.try_update(new_parent)??;
+            self.current.set(parent);
+            self.editing.set(Some(parent));
+            Some(())
+        });
+        if added.is_none() {
+            return;
+        }
+        scroll_to_current(self.axis.get_untracked());
+    }
+    /// Copies `id` and everything below it next to the original, and moves to the copy.
+    fn duplicate(self, id: u128) {
+        let duplicate = |data: &mut Data| data.duplicate_subtree(id, true);
+        let copied = batch(|| {
+            let copy = self.data.try_update(duplicate)??;
+            self.current.set(copy);
+            Some(())
+        });
+        if copied.is_none() {
+            return;
+        }
+        scroll_to_current(self.axis.get_untracked());
+    }
+    fn toggle_collapsed(self, id: u128) {
+        self.collapsed.update(|collapsed| {
+            if !collapsed.remove(&id) {
+                collapsed.insert(id);
+            }
+        });
+    }
+    fn toggle_bookmark(self, id: u128) {
+        self.bookmarks.update(|bookmarks| {
+            if !bookmarks.remove(&id) {
+                bookmarks.insert(id);
+            }
+        });
+    }
+    /// Hides everything outside the lineage of `id`, and moves to it.
+    fn focus(self, id: u128) {
+        batch(|| {
+            self.focus.set(Some(id));
+            self.current.set(id);
+        });
+    }
+    /// Deletes `id`, moving away from it first if it is current. Can be undone.
+    fn remove(self, id: u128) {
+        batch(|| {
+            let Some(Some(entry)) = self.data.try_update(|data| data.remove_entry(id)) else {
+                return;
+            };
+            if self.current.get_untracked() == id {
+                let next = entry.parents.first().copied().or_else(|| {
+                    self.data
+                        .with_untracked(|data| data.roots().first().copied())
+                });
+                self.current.set(next.unwrap_or_default());
+            }
+        });
+    }
+    fn save_text(self, id: u128, text: String) {
+        if self.editing.get_untracked() != Some(id) {
+            return; // Cancelled.
+        }
+        batch(|| {
+            self.editing.set(None);
+            self.data.update(|data| data.set_text(id, text));
+        });
+    }
+}
+
+/// The active parent and child of each entry, kept across re-renders.
+///
+/// Entries without a selection default to their first parent/child.
+#[derive(Debug, Clone, Copy)]
+struct Selection {
+    parents: RwSignal<BTreeMap<u128, u128>>,
+    children: RwSignal<BTreeMap<u128, u128>>,
+}
+impl Selection {
+    fn new() -> Self {
+        Self {
+            parents: RwSignal::new(BTreeMap::new()),
+            children: RwSignal::new(BTreeMap::new()),
+        }
+    }
+    fn parent(self, data: &Data, id: u128) -> Option<u128> {
+        let parents: Vec<_> = data.parents(id).collect();
+        self.parents.with(|selected| pick(selected, id, &parents))
+    }
+    fn child(self, data: &Data, id: u128) -> Option<u128> {
+        let children: Vec<_> = data.children(id).collect();
+        self.children.with(|selected| pick(selected, id, &children))
+    }
+    /// The entries along the active path through `current`, both up and down.
+    fn active_path(self, data: History<Data>, current: RwSignal<u128>) -> Signal<BTreeSet<u128>> {
+        let path = create_memo(move |_| {
+            let current = current.get();
+            data.with(|data| {
+                let upstream = self.upstream(data, current);
+                let downstream = self.downstream(data, current);
+                upstream.into_iter().chain(downstream).collect()
+            })
+        });
+        path.into()
+    }
+    /// `id` followed by its active parent, grandparent, and so on.
+    fn upstream(self, data: &Data, id: u128) -> Vec<u128> {
+        let mut path = vec![id];
+        while let Some(parent) = self.parent(data, *path.last().unwrap()) {
+            if path.contains(&parent) {
+                break;
+            }
+            path.push(parent);
+        }
+        path
+    }
+    /// `id` followed by its active child, grandchild, and so on.
+    fn downstream(self, data: &Data, id: u128) -> Vec<u128> {
+        let mut path = vec![id];
+        while let Some(child) = self.child(data, *path.last().unwrap()) {
+            if path.contains(&child) {
+                break;
+            }
+            path.push(child);
+        }
+        path
+    }
+    fn set_parent(self, id: u128, parent: u128) {
+        self.parents.update(|selected| {
+            selected.insert(id, parent);
+        });
+    }
+    fn set_child(self, id: u128, child: u128) {
+        self.children.update(|selected| {
+            selected.insert(id, child);
+        });
+    }
+}
+/// The selection for `id` if it is still among `options`, or the first option.
+fn pick(selected: &BTreeMap<u128, u128>, id: u128, options: &[u128]) -> Option<u128> {
+    selected
+        .get(&id)
+        .filter(|s| options.contains(s))
+        .or(options.first())
+        .copied()
+}
+
+/// Scrolls `row` so the card for `id` is the first visible one, if it isn't already.
+fn scroll_row_to(
+    row: &HtmlElement<html::Div>,
+    ids: &[u128],
+    elements: &[HtmlElement<html::Div>],
+    id: u128,
+    view: &ViewportSize,
+    axis: Axis,
+) {
+    if first_visible_element(ids, elements, view, axis).0 == id {
+        return;
+    }
+    let Some(i) = ids.iter().position(|&other| other == id) else {
+        return;
+    };
+    let (from, to) = (
+        row.get_bounding_client_rect(),
+        elements[i].get_bounding_client_rect(),
+    );
+    match axis {
+        Axis::Horizontal => row.scroll_by_with_x_and_y(to.left() - from.left(), 0.),
+        Axis::Vertical => row.scroll_by_with_x_and_y(0., to.top() - from.top()),
+    }
+}
+/// Brings the current card into view, after it was changed from outside the graph.
+///
+/// Rows scroll themselves along `axis`, so only the page is scrolled, across it. This is animated
+/// unless the user prefers reduced motion. Rows don't scroll meanwhile, so `restore_position`
+/// won't interrupt it.
+fn scroll_to_current(axis: Axis) {
+    let current = leptos::document().query_selector(".graph > .card");
+    if let Ok(Some(current)) = current {
+        let smooth = !prefers_reduced_motion().get_untracked();
+        scroll_into_view_centered(&current, axis.cross(), smooth);
+    }
+}
+
 fn first_visible_element(
     ids: &[u128],
     elements: &[HtmlElement<html::Div>],
+    view: &ViewportSize,
+    axis: Axis,
 ) -> (u128, HtmlElement<html::Div>) {
-    let view = ViewportSize::from_global();
-    for (id, e) in ids.iter().zip(elements) {
-        match Visibility::horizontal_from_element(e.deref(), &view) {
-            Visibility::Before => {}
-            Visibility::PeekingBefore(_) | Visibility::Inside => return (*id, e.clone()),
-            Visibility::PeekingAfter(_) | Visibility::After | Visibility::Straddling(_) => {
-                unreachable!()
+    let visibilities = elements
+        .iter()
+        .map(|e| Visibility::from_element(e.deref(), view, axis));
+    let i = first_visible(visibilities).expect("rows are never empty");
+    (ids[i], elements[i].clone())
+}
+
+/// Whether the event is directed at a text field, in which case shortcuts should be ignored.
+fn is_typing(e: &ev::KeyboardEvent) -> bool {
+    e.target()
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .is_some_and(|e| matches!(e.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+}
+
+/// The node in the URL hash, as in `#node=12345`.
+fn hash_node() -> Option<u128> {
+    let hash = leptos::window().location().hash().ok()?;
+    hash.strip_prefix("#node=")?.parse().ok()
+}
+fn set_hash_node(id: u128) {
+    let location = leptos::window().location();
+    let hash = format!("#node={id}");
+    if location.hash().ok().as_deref() == Some(hash.as_str()) {
+        return;
+    }
+    if let Err(e) = location.set_hash(&hash) {
+        log::warn!("Failed to update the URL: {e:?}");
+    }
+}
+
+/// The byte range of the first case-insensitive occurrence of `query` in `text`.
+fn find_ignore_case(text: &str, query: &str) -> Option<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return None;
+    }
+    text.char_indices().find_map(|(start, _)| {
+        let mut query = query.iter();
+        for (i, c) in text[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                if query.next() != Some(&lower) {
+                    return None;
+                }
+            }
+            if query.len() == 0 {
+                return Some(start..start + i + c.len_utf8());
             }
         }
-    }
+        None
+    })
+}
+
+/// Saves `contents` as a file named `name` through a temporary object URL.
+fn download(name: &str, contents: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let blob = web_sys::Blob::new_with_str_sequence(&parts)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    html::a()
+        .attr("href", url.clone())
+        .attr("download", name.to_owned())
+        .click();
 
-    unreachable!()
+    // Give the browser a moment to start the download before revoking.
+    set_timeout(
+        move || {
+            if let Err(e) = web_sys::Url::revoke_object_url(&url) {
+                log::warn!("Failed to revoke {url}: {e:?}");
+            }
+        },
+        Duration::from_secs(1),
+    );
+    Ok(())
 }
 
 fn restore_position(at: f64, e: HtmlElement<html::Div>, spacer: RwSignal<f64>) {
@@ -256,11 +1217,102 @@ fn restore_position(at: f64, e: HtmlElement<html::Div>, spacer: RwSignal<f64>) {
     window.scroll_to_with_x_and_y(0., delta);
 }
 
-fn card(id: u128, entry: &Entry) -> HtmlElement<html::Div> {
+fn card(id: u128, entry: &Entry, children: usize, ctx: GraphCtx) -> HtmlElement<html::Div> {
+    let parents = entry.parents.len();
+    let modified = entry.modified.map(|time| {
+        let time = time.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+        format!("Last edited {time}")
+    });
     html::div()
         .attr("card-id", id)
+        .attr("title", modified)
         .class("card", true)
-        .child(entry.text.clone())
+        .class("collapsed", move || ctx.collapsed.with(|c| c.contains(&id)))
+        .class("pinned", move || ctx.bookmarks.with(|b| b.contains(&id)))
+        .class("on-path", move || {
+            ctx.active_path.with(|path| path.contains(&id))
+        })
+        .on(ev::click, move |_| {
+            if ctx.current.get_untracked() != id {
+                ctx.current.set(id);
+                scroll_to_current(ctx.axis.get_untracked());
+            }
+        })
+        .on(ev::dblclick, move |_| ctx.editing.set(Some(id)))
+        .child(
+            html::span()
+                .class("degree", true)
+                .attr("title", format!("{parents} parents, {children} children"))
+                .child(format!(
+                    "↑{} ↓{}",
+                    format_count(parents as u64),
+                    format_count(children as u64)
+                )),
+        )
+        .child({
+            let text = entry.text.clone();
+            move || {
+                if ctx.editing.get() == Some(id) {
+                    card_editor(id, &text, ctx).into_view()
+                } else {
+                    html::div()
+                        .class("text", true)
+                        .inner_html(markdown::to_html(&text))
+                        .into_view()
+                }
+            }
+        })
+        .child(
+            html::div()
+                .class("actions", true)
+                .child(card_action("Add a parent", "+↑", move || {
+                    ctx.add_parent(id)
+                }))
+                .child(card_action("Add a child", "+↓", move || {
+                    ctx.add_child(id)
+                }))
+                .child(card_action(
+                    "Duplicate with everything below",
+                    "⧉",
+                    move || ctx.duplicate(id),
+                ))
+                .child(card_action("Collapse or expand", "⊟", move || {
+                    ctx.toggle_collapsed(id)
+                }))
+                .child(card_action("Pin or unpin", "★", move || {
+                    ctx.toggle_bookmark(id)
+                }))
+                .child(card_action("Focus on this", "◎", move || ctx.focus(id)))
+                .child(card_action("Delete", "×", move || ctx.remove(id))),
+        )
+}
+/// A button on a card, that doesn't also click the card.
+fn card_action(
+    title: &'static str,
+    label: &'static str,
+    f: impl Fn() + 'static,
+) -> HtmlElement<html::Button> {
+    html::button()
+        .attr("title", title)
+        .child(label)
+        .on(ev::click, move |e| {
+            e.stop_propagation();
+            f();
+        })
+}
+fn card_editor(id: u128, text: &str, ctx: GraphCtx) -> HtmlElement<html::Textarea> {
+    html::textarea()
+        .prop("value", text.to_owned())
+        .on(ev::click, |e| e.stop_propagation())
+        .on(ev::blur, move |e| ctx.save_text(id, event_target_value(&e)))
+        .on(ev::keydown, move |e| {
+            if e.key() == "Escape" {
+                ctx.editing.set(None);
+            }
+        })
+        .on_mount(|e| {
+            let _ = e.focus();
+        })
 }
 fn empty_card(id: u128, message: impl AsRef<str>) -> HtmlElement<html::Div> {
     let message = message.as_ref().to_owned();
@@ -291,27 +1343,7 @@ fn explanation() -> impl IntoView {
         .into_view()
 }
 
-impl Data {
-    fn to_json(&self) -> String {
-        serde_json::to_string_pretty(self).unwrap()
-    }
-    fn from_json(raw: &str) -> Result<Self, ()> {
-        let entries: BTreeMap<u128, Entry> = serde_json::from_str(raw).map_err(drop)?;
-        Ok(Self::from_raw(entries))
-    }
-    fn from_raw(entries: BTreeMap<u128, Entry>) -> Self {
-        let mut children: BTreeMap<u128, BTreeSet<u128>> = BTreeMap::new();
-        for (id, entry) in &entries {
-            for p in &entry.parents {
-                children.entry(*p).or_default().insert(*id);
-            }
-        }
-
-        Self { entries, children }
-    }
-}
-
 fn initial_data() -> Data {
     const INTIAL_DATA: &str = include_str!("./lol.json");
-    Data::from_json(INTIAL_DATA).unwrap()
+    Data::from_json(INTIAL_DATA).unwrap_or_else(|e| panic!("Invalid initial data: {e}"))
 }
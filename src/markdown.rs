@@ -0,0 +1,74 @@
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+
+/// Renders `text` as HTML that is safe to insert into the page.
+///
+/// Any text is valid Markdown, so this can't fail. Raw HTML is escaped rather than passed through,
+/// and links or images with unsafe urls (like `javascript:`) are reduced to their text.
+pub fn to_html(text: &str) -> String {
+    let mut dropped = false;
+    let events =
+        Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH).filter_map(|event| match event {
+            Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+            Event::Start(Tag::Link { ref dest_url, .. } | Tag::Image { ref dest_url, .. })
+                if !is_safe_url(dest_url) =>
+            {
+                dropped = true;
+                None
+            }
+            Event::End(TagEnd::Link | TagEnd::Image) if dropped => {
+                dropped = false;
+                None
+            }
+            event => Some(event),
+        });
+
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Relative urls, or absolute ones with a known harmless scheme.
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim_start();
+    match url.find([':', '/', '?', '#']) {
+        Some(i) if url[i..].starts_with(':') => {
+            let scheme = url[..i].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(to_html("Plain text"), "<p>Plain text</p>\n");
+        assert_eq!(
+            to_html("**Bold** and [a link](https://example.com)"),
+            "<p><strong>Bold</strong> and <a href=\"https://example.com\">a link</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_to_html_sanitized() {
+        assert_eq!(
+            to_html("<script>alert(1)</script>"),
+            "&lt;script&gt;alert(1)&lt;/script&gt;"
+        );
+        assert_eq!(
+            to_html("Hi <img src=x onerror=alert(1)>"),
+            "<p>Hi &lt;img src=x onerror=alert(1)&gt;</p>\n"
+        );
+        assert_eq!(
+            to_html("[click](javascript:alert(1)) ![](JavaScript:alert(1))"),
+            "<p>click </p>\n"
+        );
+        assert_eq!(
+            to_html("[relative](./page#part)"),
+            "<p><a href=\"./page#part\">relative</a></p>\n"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use leptos::{
+    on_cleanup,
+    wasm_bindgen::{closure::Closure, JsCast},
+    window, RwSignal, Signal, SignalSet,
+};
+
+use crate::leptos_ext::rc_signal::with_intentional_leak;
+
+/// Whether the media `query` matches right now.
+pub fn matches_media(query: &str) -> bool {
+    matches!(window().match_media(query), Ok(Some(list)) if list.matches())
+}
+
+/// Whether the media `query` matches, kept up to date as it changes.
+///
+/// Invalid queries and browsers without `matchMedia` never match.
+pub fn use_media_query(query: &str) -> Signal<bool> {
+    let Ok(Some(list)) = window().match_media(query) else {
+        return Signal::derive(|| false);
+    };
+    let matches = RwSignal::new(list.matches());
+
+    let callback = Closure::<dyn FnMut()>::new({
+        let list = list.clone();
+        move || matches.set(list.matches())
+    });
+    list.add_event_listener_with_callback("change", callback.as_ref().unchecked_ref())
+        .unwrap();
+
+    on_cleanup(move || {
+        let _ =
+            list.remove_event_listener_with_callback("change", callback.as_ref().unchecked_ref());
+    });
+
+    matches.into()
+}
+
+/// Whether the user asked for less motion, in which case animations should jump straight to
+/// their end instead. Shared by the whole app.
+pub fn prefers_reduced_motion() -> Signal<bool> {
+    thread_local! {
+        static REDUCED_MOTION: Signal<bool> =
+            with_intentional_leak(|| use_media_query("(prefers-reduced-motion: reduce)"));
+    }
+    REDUCED_MOTION.with(|signal| *signal)
+}
@@ -0,0 +1,90 @@
+use crate::{
+    data::local_storage,
+    leptos_ext::{
+        rc_signal::{with_intentional_leak, LeakedRwSignal},
+        ReadSignalExt,
+    },
+    media::matches_media,
+};
+
+const STORAGE_KEY: &str = "locallyvalid-theme";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+impl Theme {
+    pub fn toggled(self) -> Self {
+        match self {
+            Theme::Light => Theme::Dark,
+            Theme::Dark => Theme::Light,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(Theme::Light),
+            "dark" => Some(Theme::Dark),
+            _ => None,
+        }
+    }
+
+    fn from_system() -> Self {
+        if matches_media("(prefers-color-scheme: dark)") {
+            Theme::Dark
+        } else {
+            Theme::Light
+        }
+    }
+    fn load() -> Option<Self> {
+        match local_storage()?.get_item(STORAGE_KEY) {
+            Ok(name) => Self::from_name(&name?),
+            Err(e) => {
+                log::warn!("Failed to read saved theme: {e:?}");
+                None
+            }
+        }
+    }
+    fn save(self) {
+        let Some(storage) = local_storage() else {
+            return;
+        };
+        if let Err(e) = storage.set_item(STORAGE_KEY, self.name()) {
+            log::warn!("Failed to save theme: {e:?}");
+        }
+    }
+    /// Sets the `dark` class on the root element, which the styles key off.
+    fn apply(self) {
+        let Some(root) = leptos::document().document_element() else {
+            return;
+        };
+        let _ = root
+            .class_list()
+            .toggle_with_force("dark", self == Theme::Dark);
+    }
+}
+
+/// The app-wide theme, starting from the one last picked or else the system preference.
+///
+/// Changes are applied to the page right away, and saved so they survive reloads.
+pub fn theme() -> LeakedRwSignal<Theme> {
+    thread_local! {
+        static THEME: LeakedRwSignal<Theme> = {
+            let theme = LeakedRwSignal::new(Theme::load().unwrap_or_else(Theme::from_system));
+            with_intentional_leak(|| {
+                theme.for_each(|theme| theme.apply());
+                // Only once picked, so the system preference keeps applying until then.
+                theme.for_each_after_first(|theme| theme.save());
+            });
+            theme
+        };
+    }
+    THEME.with(|theme| *theme)
+}
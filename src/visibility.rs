@@ -1,6 +1,33 @@
-use leptos::window;
+use js_sys::Array;
+use leptos::{
+    document, ev, on_cleanup,
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    window, window_event_listener, RwSignal, Signal, SignalSet,
+};
 use std::ops::Range;
-use web_sys::{DomRect, Element};
+use web_sys::{
+    DomRect, Element, IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit,
+    ScrollBehavior, ScrollToOptions,
+};
+
+/// How many steps the `IntersectionObserver` in [`use_visibility`] splits the visible fraction into.
+const OBSERVER_STEPS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Axis {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+impl Axis {
+    /// The other axis.
+    pub fn cross(self) -> Self {
+        match self {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Visibility {
@@ -18,22 +45,57 @@ pub enum Visibility {
     Straddling(f64),
 }
 impl Visibility {
+    pub fn from_element(element: impl AsRef<Element>, view: &ViewportSize, axis: Axis) -> Self {
+        match axis {
+            Axis::Horizontal => Self::horizontal_from_element(element, view),
+            Axis::Vertical => Self::vertical_from_element(element, view),
+        }
+    }
+
+    pub fn from_rect(rect: &DomRect, view: &ViewportSize, axis: Axis) -> Self {
+        match axis {
+            Axis::Horizontal => Self::horizontal_from_rect(rect, view),
+            Axis::Vertical => Self::vertical_from_rect(rect, view),
+        }
+    }
+
+    /// Like [`Self::from_element`], but against the client area of a scrolling `container`
+    /// instead of the window.
+    pub fn from_element_in(element: impl AsRef<Element>, container: &Element, axis: Axis) -> Self {
+        let rect = element.as_ref().get_bounding_client_rect();
+        let outer = container.get_bounding_client_rect();
+        match axis {
+            Axis::Horizontal => {
+                let start = outer.left() + f64::from(container.client_left());
+                let end = start + f64::from(container.client_width());
+                Self::new(rect.left()..rect.right(), start..end)
+            }
+            Axis::Vertical => {
+                let start = outer.top() + f64::from(container.client_top());
+                let end = start + f64::from(container.client_height());
+                Self::new(rect.top()..rect.bottom(), start..end)
+            }
+        }
+    }
+
     pub fn vertical_from_element(element: impl AsRef<Element>, view: &ViewportSize) -> Self {
         Self::vertical_from_rect(&element.as_ref().get_bounding_client_rect(), view)
     }
     pub fn vertical_from_rect(rect: &DomRect, view: &ViewportSize) -> Self {
-        Self::new(rect.top()..rect.bottom(), view.height)
+        Self::new(rect.top()..rect.bottom(), view.vertical())
     }
 
     pub fn horizontal_from_element(element: impl AsRef<Element>, view: &ViewportSize) -> Self {
         Self::horizontal_from_rect(&element.as_ref().get_bounding_client_rect(), view)
     }
     pub fn horizontal_from_rect(rect: &DomRect, view: &ViewportSize) -> Self {
-        Self::new(rect.left()..rect.right(), view.width)
+        Self::new(rect.left()..rect.right(), view.horizontal())
     }
 
-    fn new(range: Range<f64>, window: f64) -> Self {
-        let Range { start, end } = range;
+    /// Where `range` falls relative to `window`, both in the same coordinates.
+    fn new(range: Range<f64>, window: Range<f64>) -> Self {
+        let (start, end) = (range.start - window.start, range.end - window.start);
+        let window = window.end - window.start;
 
         match f64::total_cmp(&start, &0.) {
             std::cmp::Ordering::Less => {
@@ -113,6 +175,10 @@ impl Visibility {
     pub fn is_visible(self) -> bool {
         self.fraction_visible().is_some()
     }
+    /// Whether at least `fraction` of the box is visible, useful to defer work until it matters.
+    pub fn is_visible_at_least(self, fraction: f64) -> bool {
+        self.fraction_visible().is_some_and(|f| f >= fraction)
+    }
     pub fn is_first_visible(self) -> bool {
         match self {
             Visibility::PeekingBefore(_) | Visibility::Straddling(_) => true,
@@ -125,17 +191,333 @@ impl Visibility {
     }
 }
 
+/// Visibility along both axes at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Visibility2D {
+    pub horizontal: Visibility,
+    pub vertical: Visibility,
+}
+impl Visibility2D {
+    pub fn from_element(element: impl AsRef<Element>, view: &ViewportSize) -> Self {
+        Self::from_rect(&element.as_ref().get_bounding_client_rect(), view)
+    }
+    pub fn from_rect(rect: &DomRect, view: &ViewportSize) -> Self {
+        Self {
+            horizontal: Visibility::horizontal_from_rect(rect, view),
+            vertical: Visibility::vertical_from_rect(rect, view),
+        }
+    }
+
+    pub fn is_fully_visible(self) -> bool {
+        self.horizontal == Visibility::Inside && self.vertical == Visibility::Inside
+    }
+    /// The fraction of the box's area that is visible, or `None` if it is off-screen.
+    pub fn area_fraction_visible(self) -> Option<f64> {
+        Some(self.horizontal.fraction_visible()? * self.vertical.fraction_visible()?)
+    }
+}
+
+/// The index of the first visible box in a sequence laid out along one axis.
+///
+/// If none is visible, this falls back to the last one before the viewport, or the first one.
+/// Only returns `None` when there are no boxes.
+pub fn first_visible(visibilities: impl IntoIterator<Item = Visibility>) -> Option<usize> {
+    let mut last_before = None;
+    for (i, visibility) in visibilities.into_iter().enumerate() {
+        match visibility {
+            Visibility::Before => last_before = Some(i),
+            Visibility::PeekingBefore(_)
+            | Visibility::Inside
+            | Visibility::PeekingAfter(_)
+            | Visibility::Straddling(_) => return Some(i),
+            Visibility::After => return Some(last_before.unwrap_or(i)),
+        }
+    }
+    last_before
+}
+
+/// Tracks the visibility of `element` along `axis` without polling on scroll.
+///
+/// Backed by an `IntersectionObserver`, so the signal only updates when the visible fraction
+/// crosses one of [`OBSERVER_STEPS`] thresholds. The initial value is read synchronously.
+pub fn use_visibility(element: &Element, axis: Axis) -> Signal<Visibility> {
+    let visibility = RwSignal::new(Visibility::from_element(
+        element,
+        &ViewportSize::from_global(),
+        axis,
+    ));
+
+    let callback = Closure::<dyn FnMut(Array)>::new(move |entries: Array| {
+        let Some(entry) = entries.iter().last() else {
+            return;
+        };
+        let entry: IntersectionObserverEntry = entry.unchecked_into();
+        let target = entry.bounding_client_rect();
+        let target = (target.left()..target.right(), target.top()..target.bottom());
+        let root = match entry.root_bounds() {
+            Some(root) => (root.left()..root.right(), root.top()..root.bottom()),
+            None => {
+                let view = ViewportSize::from_global();
+                (view.horizontal(), view.vertical())
+            }
+        };
+        visibility.set(observed_visibility(target, root, axis));
+    });
+
+    let thresholds: Array = (0..=OBSERVER_STEPS)
+        .map(|i| JsValue::from(f64::from(i) / f64::from(OBSERVER_STEPS)))
+        .collect();
+    let observer = IntersectionObserver::new_with_options(
+        callback.as_ref().unchecked_ref(),
+        IntersectionObserverInit::new().threshold(&thresholds),
+    )
+    .unwrap();
+    observer.observe(element);
+
+    on_cleanup(move || {
+        observer.disconnect();
+        drop(callback);
+    });
+
+    visibility.into()
+}
+
+/// Where an observed `target` is within the observer's `root`, both given as `(x, y)` ranges
+/// in client coordinates.
+fn observed_visibility(
+    target: (Range<f64>, Range<f64>),
+    root: (Range<f64>, Range<f64>),
+    axis: Axis,
+) -> Visibility {
+    match axis {
+        Axis::Horizontal => Visibility::new(target.0, root.0),
+        Axis::Vertical => Visibility::new(target.1, root.1),
+    }
+}
+
+/// Scrolls the window so `element` is centered along `axis`, without going past the document.
+///
+/// With `smooth` the browser animates the scroll, otherwise it jumps there.
+pub fn scroll_into_view_centered(element: &Element, axis: Axis, smooth: bool) {
+    let window = window();
+    let view = ViewportSize::from_global();
+    let rect = element.get_bounding_client_rect();
+    let (x, y) = (window.scroll_x().unwrap(), window.scroll_y().unwrap());
+    let Some(root) = document().document_element() else {
+        return;
+    };
+
+    let (x, y) = match axis {
+        Axis::Horizontal => {
+            let range = (x + rect.left())..(x + rect.right());
+            let x = centered_scroll(range, view.width, root.scroll_width().into());
+            (x, y)
+        }
+        Axis::Vertical => {
+            let range = (y + rect.top())..(y + rect.bottom());
+            let y = centered_scroll(range, view.height, root.scroll_height().into());
+            (x, y)
+        }
+    };
+
+    let mut options = ScrollToOptions::new();
+    let behavior = if smooth {
+        ScrollBehavior::Smooth
+    } else {
+        ScrollBehavior::Instant
+    };
+    options.left(x).top(y).behavior(behavior);
+    window.scroll_to_with_scroll_to_options(&options);
+}
+/// The scroll offset that centers `range` (in document coordinates) in a `window` wide viewport.
+fn centered_scroll(range: Range<f64>, window: f64, document: f64) -> f64 {
+    let center = (range.start + range.end) / 2.;
+    let max = (document - window).max(0.);
+    (center - window / 2.).clamp(0., max)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ViewportSize {
     width: f64,
     height: f64,
+    /// How much of each edge is covered, for example by fixed headers.
+    top: f64,
+    bottom: f64,
+    left: f64,
+    right: f64,
 }
 impl ViewportSize {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            top: 0.,
+            bottom: 0.,
+            left: 0.,
+            right: 0.,
+        }
+    }
     pub fn from_global() -> Self {
         let window = window();
+        Self::new(
+            window.inner_width().unwrap().as_f64().unwrap(),
+            window.inner_height().unwrap().as_f64().unwrap(),
+        )
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    /// Excludes the covered edges from the visible region, so boxes under a fixed header
+    /// don't count as visible.
+    pub fn with_insets(self, top: f64, bottom: f64, left: f64, right: f64) -> Self {
         Self {
-            width: window.inner_width().unwrap().as_f64().unwrap(),
-            height: window.inner_height().unwrap().as_f64().unwrap(),
+            top,
+            bottom,
+            left,
+            right,
+            ..self
         }
     }
+
+    /// The visible region along the horizontal axis, in client coordinates.
+    fn horizontal(&self) -> Range<f64> {
+        self.left..(self.width - self.right).max(self.left)
+    }
+    /// The visible region along the vertical axis, in client coordinates.
+    fn vertical(&self) -> Range<f64> {
+        self.top..(self.height - self.bottom).max(self.top)
+    }
+
+    /// The size of the window, kept up to date as it is resized or rotated.
+    pub fn signal() -> Signal<Self> {
+        let size = RwSignal::new(Self::from_global());
+        let handle = window_event_listener(ev::resize, move |_| size.set(Self::from_global()));
+        on_cleanup(move || handle.remove());
+        size.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observed_visibility() {
+        // A card half scrolled out of the left edge of a root offset from the window.
+        let target = (50.0..250., 100.0..200.);
+        let root = (150.0..550., 0.0..400.);
+        assert_eq!(
+            observed_visibility(target.clone(), root.clone(), Axis::Horizontal),
+            Visibility::PeekingBefore(0.5)
+        );
+        assert_eq!(
+            observed_visibility(target, root.clone(), Axis::Vertical),
+            Visibility::Inside
+        );
+
+        let below = (200.0..300., 450.0..500.);
+        assert_eq!(
+            observed_visibility(below, root, Axis::Vertical),
+            Visibility::After
+        );
+    }
+
+    #[test]
+    fn test_first_visible_wider_than_viewport() {
+        let wide = Visibility::new(-50.0..1000.0, 0.0..400.);
+        assert_eq!(wide, Visibility::Straddling(400. / 1050.));
+
+        let row = [
+            Visibility::new(-2000.0..-1050.0, 0.0..400.),
+            wide,
+            Visibility::new(1000.0..2050.0, 0.0..400.),
+        ];
+        assert_eq!(first_visible(row), Some(1));
+
+        let row = [
+            Visibility::new(100.0..1150.0, 0.0..400.),
+            Visibility::new(1150.0..2200.0, 0.0..400.),
+        ];
+        assert_eq!(first_visible(row), Some(0));
+
+        let row = [Visibility::Before, Visibility::After];
+        assert_eq!(first_visible(row), Some(0));
+        assert_eq!(
+            first_visible([Visibility::Before, Visibility::Before]),
+            Some(1)
+        );
+        assert_eq!(first_visible([]), None);
+    }
+
+    #[test]
+    fn test_is_visible_at_least() {
+        assert!(Visibility::Inside.is_visible_at_least(1.));
+        assert!(Visibility::PeekingBefore(0.25).is_visible_at_least(0.25));
+        assert!(!Visibility::PeekingAfter(0.2).is_visible_at_least(0.25));
+        assert!(!Visibility::Before.is_visible_at_least(0.));
+    }
+
+    #[test]
+    fn test_visibility_2d() {
+        let inside = Visibility2D {
+            horizontal: Visibility::Inside,
+            vertical: Visibility::Inside,
+        };
+        assert!(inside.is_fully_visible());
+        assert_eq!(inside.area_fraction_visible(), Some(1.));
+
+        let corner = Visibility2D {
+            horizontal: Visibility::PeekingBefore(0.5),
+            vertical: Visibility::PeekingAfter(0.25),
+        };
+        assert!(!corner.is_fully_visible());
+        assert_eq!(corner.area_fraction_visible(), Some(0.125));
+
+        let off_screen = Visibility2D {
+            horizontal: Visibility::Inside,
+            vertical: Visibility::After,
+        };
+        assert_eq!(off_screen.area_fraction_visible(), None);
+    }
+
+    #[test]
+    fn test_centered_scroll() {
+        assert_eq!(centered_scroll(1000.0..1200.0, 400., 3000.), 900.);
+        // Clamped at both ends of the document.
+        assert_eq!(centered_scroll(0.0..100.0, 400., 3000.), 0.);
+        assert_eq!(centered_scroll(2900.0..3000.0, 400., 3000.), 2600.);
+        // Documents shorter than the viewport can't scroll.
+        assert_eq!(centered_scroll(100.0..200.0, 400., 300.), 0.);
+    }
+
+    #[test]
+    fn test_insets() {
+        let view = ViewportSize::new(400., 800.).with_insets(40., 0., 10., 20.);
+        assert_eq!(view.vertical(), 40.0..800.0);
+        assert_eq!(view.horizontal(), 10.0..380.0);
+
+        // Under the header, so only partially visible.
+        let under_header = Visibility::new(20.0..120.0, view.vertical());
+        assert_eq!(under_header, Visibility::PeekingBefore(0.8));
+        assert_eq!(
+            Visibility::new(40.0..120.0, view.vertical()),
+            Visibility::Inside
+        );
+        assert_eq!(
+            Visibility::new(0.0..40.0, view.vertical()),
+            Visibility::Before
+        );
+        assert_eq!(
+            Visibility::new(300.0..400.0, view.horizontal()),
+            Visibility::PeekingAfter(0.8)
+        );
+    }
 }